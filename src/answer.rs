@@ -0,0 +1,320 @@
+use std::{
+  fmt::{Display, Formatter, Result as FmtResult},
+  ops::Range,
+};
+
+use crate::grid::Grid;
+
+/// The result of solving one part of a puzzle.
+///
+/// Some puzzles produce their answer as block letters drawn on a grid rather than as a
+/// number or string; those are held as `Letters` and read back into text with [`ocr`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+  Number(i128),
+  Text(String),
+  Letters(Vec<Vec<bool>>),
+}
+
+impl Answer {
+  /// Returns the answer as it should be submitted, or `None` if it holds block letters
+  /// that couldn't be recognized.
+  pub fn text(&self) -> Option<String> {
+    match self {
+      Answer::Number(n) => Some(n.to_string()),
+      Answer::Text(s) => Some(s.clone()),
+      Answer::Letters(pixels) => ocr(pixels),
+    }
+  }
+
+  fn pixels(&self) -> &[Vec<bool>] {
+    match self {
+      Answer::Letters(pixels) => pixels,
+      _ => &[],
+    }
+  }
+}
+
+impl Display for Answer {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    if let Some(text) = self.text() {
+      return write!(f, "{text}");
+    }
+
+    // Fall back to drawing the letters so they can at least be read by eye
+    for row in self.pixels() {
+      writeln!(f)?;
+      for &lit in row {
+        write!(f, "{}", if lit { '#' } else { '.' })?;
+      }
+    }
+    Ok(())
+  }
+}
+
+macro_rules! impl_from_integer {
+  ($($ty:ty),*) => {
+    $(
+      impl From<$ty> for Answer {
+        fn from(value: $ty) -> Self {
+          Answer::Number(value as i128)
+        }
+      }
+    )*
+  };
+}
+
+impl_from_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+  fn from(value: String) -> Self {
+    Answer::Text(value)
+  }
+}
+
+impl From<&str> for Answer {
+  fn from(value: &str) -> Self {
+    Answer::Text(value.to_string())
+  }
+}
+
+impl From<Vec<Vec<bool>>> for Answer {
+  fn from(value: Vec<Vec<bool>>) -> Self {
+    Answer::Letters(value)
+  }
+}
+
+impl From<Grid<bool>> for Answer {
+  fn from(value: Grid<bool>) -> Self {
+    Answer::Letters(value.rows().map(|row| row.map(|c| *c).collect()).collect())
+  }
+}
+
+/// Reads block letters in either of the standard AoC fonts (4x6 or 6x10) into a string.
+///
+/// Glyphs are separated by columns of unlit pixels (except after a glyph that fills its
+/// whole cell), and blank rows or columns around the edges are ignored. Returns `None` if any glyph isn't recognized.
+pub fn ocr(pixels: &[Vec<bool>]) -> Option<String> {
+  let lit_rows = pixels
+    .iter()
+    .filter(|row| row.iter().any(|&lit| lit))
+    .collect::<Vec<_>>();
+  let font = match lit_rows.len() {
+    6 => FONT_SMALL,
+    10 => FONT_LARGE,
+    _ => return None,
+  };
+
+  let width = lit_rows.iter().map(|row| row.len()).max()?;
+  let column_lit = |col: usize| lit_rows.iter().any(|row| lit(row, col));
+
+  let mut text = String::new();
+  let mut col = 0;
+  while col < width {
+    if !column_lit(col) {
+      col += 1;
+      continue;
+    }
+
+    let start = col;
+    while col < width && column_lit(col) {
+      col += 1;
+    }
+    text.push_str(&read_glyphs(&lit_rows, start..col, font)?);
+  }
+
+  Some(text)
+}
+
+/// Reads a run of columns with no blank column between them. That's usually one glyph, but
+/// a glyph that fills its whole cell (like `Y` in the small font) runs straight into the
+/// next one, so the run is split after whichever glyph leaves a readable remainder.
+fn read_glyphs(rows: &[&Vec<bool>], cols: Range<usize>, font: &[(char, &str)]) -> Option<String> {
+  if cols.is_empty() {
+    return Some(String::new());
+  }
+
+  font.iter().find_map(|(letter, pattern)| {
+    let glyph = glyph_pixels(pattern);
+    let end = cols.start + glyph[0].len();
+    let matches = end <= cols.end
+      && rows.iter().zip(&glyph).all(|(row, glyph_row)| {
+        (cols.start..end)
+          .map(|col| lit(row, col))
+          .eq(glyph_row.iter().copied())
+      });
+    if !matches {
+      return None;
+    }
+    let rest = read_glyphs(rows, end..cols.end, font)?;
+    Some(format!("{letter}{rest}"))
+  })
+}
+
+fn lit(row: &[bool], col: usize) -> bool {
+  row.get(col).copied().unwrap_or(false)
+}
+
+fn glyph_pixels(pattern: &str) -> Vec<Vec<bool>> {
+  let rows = pattern
+    .lines()
+    .map(|line| line.chars().map(|c| c == '#').collect::<Vec<_>>())
+    .collect::<Vec<_>>();
+
+  // Normalize away any padding columns so glyphs compare the same way they're segmented
+  let width = rows[0].len();
+  let used = (0..width)
+    .filter(|&c| rows.iter().any(|row| row[c]))
+    .collect::<Vec<_>>();
+  let (first, last) = (used[0], used[used.len() - 1]);
+  rows
+    .into_iter()
+    .map(|row| row[first..=last].to_vec())
+    .collect()
+}
+
+const FONT_SMALL: &[(char, &str)] = &[
+  ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+  ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+  ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+  ('E', "####\n#...\n###.\n#...\n#...\n####"),
+  ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+  ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+  ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+  ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+  ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+  ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+  ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+  ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+  ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+  ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+  ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+  ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+  ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+  ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const FONT_LARGE: &[(char, &str)] = &[
+  (
+    'A',
+    "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+  ),
+  (
+    'B',
+    "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+  ),
+  (
+    'C',
+    ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+  ),
+  (
+    'E',
+    "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+  ),
+  (
+    'F',
+    "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+  ),
+  (
+    'G',
+    ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+  ),
+  (
+    'H',
+    "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+  ),
+  (
+    'J',
+    "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+  ),
+  (
+    'K',
+    "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+  ),
+  (
+    'L',
+    "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+  ),
+  (
+    'N',
+    "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+  ),
+  (
+    'P',
+    "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+  ),
+  (
+    'R',
+    "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+  ),
+  (
+    'X',
+    "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+  ),
+  (
+    'Z',
+    "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+  ),
+];
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::prelude::*;
+  use indoc::indoc;
+
+  fn pixels(input: &str) -> Vec<Vec<bool>> {
+    input.parse_char_grid().map_2d(|c| c == '#')
+  }
+
+  #[test]
+  fn test_ocr_small() {
+    let letters = pixels(indoc! {"
+      #..#.####.#....#.....##..
+      #..#.#....#....#....#..#.
+      ####.###..#....#....#..#.
+      #..#.#....#....#....#..#.
+      #..#.#....#....#....#..#.
+      #..#.####.####.####..##..
+    "});
+    assert_eq!(ocr(&letters), Some("HELLO".to_string()));
+  }
+
+  #[test]
+  fn test_ocr_touching_glyphs() {
+    // `Y` fills its whole cell, so there's no blank column between it and the next letter
+    let letters = pixels(indoc! {"
+      #...##....#...#
+      #...##....#...#
+      .#.#.#.....#.#.
+      ..#..#......#..
+      ..#..#......#..
+      ..#..####...#..
+    "});
+    assert_eq!(ocr(&letters), Some("YLY".to_string()));
+  }
+
+  #[test]
+  fn test_ocr_large() {
+    let letters = pixels(indoc! {"
+      ..##....#....#
+      .#..#...#....#
+      #....#...#..#.
+      #....#...#..#.
+      #....#....##..
+      ######....##..
+      #....#...#..#.
+      #....#...#..#.
+      #....#..#....#
+      #....#..#....#
+    "});
+    assert_eq!(ocr(&letters), Some("AX".to_string()));
+  }
+
+  #[test]
+  fn test_unrecognized_letters() {
+    let answer = Answer::from(pixels("#.#\n.#.\n#.#\n.#.\n#.#\n.#."));
+    assert_eq!(answer.text(), None);
+    assert_eq!(answer.to_string(), "\n#.#\n.#.\n#.#\n.#.\n#.#\n.#.");
+  }
+}
@@ -0,0 +1,171 @@
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+  process::Command,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc,
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+use aoc::runner::PartSelection;
+use clap::Parser;
+
+/// Runs the solutions for several days, each in its own process.
+#[derive(clap::Parser)]
+struct Options {
+  /// Which days to run, e.g. `--days=1,4,7` (defaults to every day that has been built)
+  #[arg(short, long, value_delimiter = ',')]
+  days: Vec<u8>,
+
+  /// Which part to run
+  #[arg(short, long, default_value_t = PartSelection::Both)]
+  part: PartSelection,
+
+  /// How many days to run at once; leave this at 1 for the most reliable timings
+  #[arg(short, long, default_value_t = 1)]
+  jobs: usize,
+}
+
+struct DayResult {
+  day: u8,
+  output: String,
+  elapsed: Duration,
+  succeeded: bool,
+}
+
+fn main() {
+  let options = Options::parse();
+  let bin_dir = std::env::current_exe()
+    .expect("Failed to locate the current executable")
+    .parent()
+    .expect("Executable should be in a directory")
+    .to_path_buf();
+
+  let days = if options.days.is_empty() {
+    (1..=25)
+      .filter(|&day| day_binary(&bin_dir, day).exists())
+      .collect()
+  } else {
+    options.days.clone()
+  };
+
+  let start = Instant::now();
+  let cpu_start = children_cpu_time();
+  let next = AtomicUsize::new(0);
+  let (sender, receiver) = mpsc::channel();
+  let mut failures = 0;
+
+  thread::scope(|scope| {
+    for _ in 0..options.jobs.max(1) {
+      let (sender, next, days, bin_dir) = (sender.clone(), &next, &days, &bin_dir);
+      scope.spawn(move || {
+        loop {
+          let index = next.fetch_add(1, Ordering::Relaxed);
+          let Some(&day) = days.get(index) else {
+            break;
+          };
+          let result = run_day(bin_dir, day, options.part, options.jobs > 1);
+          sender
+            .send((index, result))
+            .expect("Results should still be received");
+        }
+      });
+    }
+    drop(sender);
+
+    // Days may finish out of order, so hold on to results until it's their turn
+    let mut pending = BTreeMap::new();
+    let mut up_next = 0;
+    for (index, result) in receiver {
+      pending.insert(index, result);
+      while let Some(result) = pending.remove(&up_next) {
+        print_result(&result);
+        failures += !result.succeeded as usize;
+        up_next += 1;
+      }
+    }
+  });
+
+  let wall_time = start.elapsed();
+  match children_cpu_time().zip(cpu_start) {
+    Some((end, start)) => println!(
+      "{} days in {wall_time:?} wall-clock, {:?} CPU time across all days",
+      days.len(),
+      end - start
+    ),
+    None => println!("{} days in {wall_time:?}", days.len()),
+  }
+
+  if failures > 0 {
+    println!("{failures} days failed");
+    std::process::exit(1);
+  }
+}
+
+fn day_binary(bin_dir: &Path, day: u8) -> PathBuf {
+  bin_dir
+    .join(format!("day{day:02}"))
+    .with_extension(std::env::consts::EXE_EXTENSION)
+}
+
+/// Runs one day's solution. Days running in parallel slow each other down, so their timings
+/// aren't recorded in the history.
+fn run_day(bin_dir: &Path, day: u8, part: PartSelection, parallel: bool) -> DayResult {
+  let start = Instant::now();
+  let mut command = Command::new(day_binary(bin_dir, day));
+  command.arg(format!("--part={part}"));
+  if parallel {
+    command.arg("--no-history");
+  }
+  let output = command.output();
+  let elapsed = start.elapsed();
+
+  match output {
+    Ok(output) => DayResult {
+      day,
+      output: String::from_utf8_lossy(&output.stdout).to_string()
+        + &String::from_utf8_lossy(&output.stderr),
+      elapsed,
+      succeeded: output.status.success(),
+    },
+    Err(err) => DayResult {
+      day,
+      output: format!("Failed to run day {day} ({err}); build it with `cargo build --bins`"),
+      elapsed,
+      succeeded: false,
+    },
+  }
+}
+
+fn print_result(result: &DayResult) {
+  println!("Day {:02} ({:?})", result.day, result.elapsed);
+  for line in result.output.lines() {
+    println!("  {line}");
+  }
+}
+
+/// The total user and system CPU time used by all finished child processes, where the
+/// platform can report it.
+#[cfg(unix)]
+fn children_cpu_time() -> Option<Duration> {
+  let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+  // SAFETY: getrusage only writes to the provided struct, and we only read it on success
+  let usage = unsafe {
+    if libc::getrusage(libc::RUSAGE_CHILDREN, usage.as_mut_ptr()) != 0 {
+      return None;
+    }
+    usage.assume_init()
+  };
+
+  let to_duration =
+    |time: libc::timeval| Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000);
+  Some(to_duration(usage.ru_utime) + to_duration(usage.ru_stime))
+}
+
+#[cfg(not(unix))]
+fn children_cpu_time() -> Option<Duration> {
+  None
+}
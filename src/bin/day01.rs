@@ -1,4 +1,4 @@
-use aoc::prelude::*;
+use aoc::{grid::Direction, prelude::*};
 
 solution! {
   year: 2025,
@@ -8,36 +8,23 @@ solution! {
   part_2,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Dir {
-  Left,
-  Right,
-}
-
-impl Dir {
-  fn as_i32(&self) -> i32 {
-    match self {
-      Dir::Left => -1,
-      Dir::Right => 1,
-    }
-  }
-}
-
-fn parse(input: &str) -> Vec<(Dir, i32)> {
+fn parse(input: &str) -> Vec<(Direction, i32)> {
   use parse::*;
 
-  let dir = char('L')
-    .map(|_| Dir::Left)
-    .or(char('R').map(|_| Dir::Right));
+  let dir = one_of("LR").map_res(Direction::try_from);
 
   input.parse_lines(pair(dir, i32))
 }
 
-fn part_1(input: Vec<(Dir, i32)>) -> i32 {
+fn sign(dir: Direction) -> i32 {
+  dir.offset().col as i32
+}
+
+fn part_1(input: Vec<(Direction, i32)>) -> i32 {
   let mut zeroes: i32 = 0;
   let mut position: i32 = 50;
   for (dir, dist) in input {
-    position += dir.as_i32() * dist;
+    position += sign(dir) * dist;
     position %= 100;
     if position == 0 {
       zeroes += 1;
@@ -46,7 +33,7 @@ fn part_1(input: Vec<(Dir, i32)>) -> i32 {
   zeroes
 }
 
-fn part_2(input: Vec<(Dir, i32)>) -> i32 {
+fn part_2(input: Vec<(Direction, i32)>) -> i32 {
   let mut zeroes: i32 = 0;
   let mut position: i32 = 50;
   for (dir, mut dist) in input {
@@ -59,7 +46,7 @@ fn part_2(input: Vec<(Dir, i32)>) -> i32 {
 
     // Check whether we cross zero one more time
     let start = position;
-    position += dir.as_i32() * dist;
+    position += sign(dir) * dist;
     if start != 0 && (position <= 0 || position >= 100) {
       zeroes += 1;
     }
@@ -1,5 +1,5 @@
 use aoc::{
-  grid::{Direction, Grid, GridCell},
+  grid::{Grid, GridCell, Update},
   prelude::*,
 };
 
@@ -12,18 +12,11 @@ solution! {
 }
 
 fn parse(input: &str) -> Grid<bool> {
-  Grid::new(input.parse_char_grid().map_2d(|c| c == '@'))
+  Grid::parse(input, |c| c == '@')
 }
 
 fn occupied_neighbours(cell: &GridCell<bool>) -> usize {
-  Direction::all()
-    .filter(|&dir| {
-      cell
-        .neighbour(dir)
-        .map(|neighbour| *neighbour)
-        .unwrap_or(false)
-    })
-    .count()
+  cell.count_neighbours(|&occupied| occupied)
 }
 
 fn part_1(grid: Grid<bool>) -> usize {
@@ -33,22 +26,13 @@ fn part_1(grid: Grid<bool>) -> usize {
     .count()
 }
 
-fn part_2(grid: Grid<bool>) -> u64 {
-  let mut grid = grid.clone();
-  let mut count = 0;
-  loop {
-    let start = count;
-    for coord in grid.coords() {
-      if grid[coord] && occupied_neighbours(&grid.cell(coord)) < 4 {
-        count += 1;
-        grid[coord] = false;
-      }
-    }
-    if start == count {
-      break;
-    }
-  }
-  count
+fn part_2(mut grid: Grid<bool>) -> usize {
+  grid
+    .run_until_stable(Update::InPlace, |cell| {
+      *cell && occupied_neighbours(&cell) >= 4
+    })
+    .into_iter()
+    .sum()
 }
 
 #[cfg(test)]
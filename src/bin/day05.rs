@@ -1,4 +1,5 @@
 use aoc::prelude::*;
+use std::cmp::Reverse;
 
 solution! {
   year: 2025,
@@ -33,7 +34,7 @@ fn part_1((ranges, ids): Parsed) -> usize {
 
 fn part_2((mut ranges, _): Parsed) -> u64 {
   // Sorting ensures any ranges that full subsume others are processed first
-  ranges.sort_by(|&a, &b| (b.1 - b.0).cmp(&(a.1 - a.0)));
+  ranges.sort_by_key(|&(start, end)| Reverse(end - start));
 
   let mut intervals = Vec::new();
   for &(mut start, mut end) in &ranges {
@@ -1,7 +1,5 @@
-use std::cell::Cell;
-
 use aoc::{
-  grid::{Direction, Grid},
+  grid::{Coord, Direction, Grid},
   prelude::*,
 };
 
@@ -13,40 +11,18 @@ solution! {
   part_2,
 }
 
-struct QuantumNonsense {
-  is_splitter: bool,
-  paths: Cell<u64>,
-}
-
-impl QuantumNonsense {
-  fn new(is_splitter: bool) -> Self {
-    Self {
-      is_splitter,
-      paths: Cell::new(0),
-    }
-  }
-
-  fn increment_paths(&self, count: u64) {
-    let current = self.paths.get();
-    self.paths.set(current + count);
-  }
-
-  fn paths(&self) -> u64 {
-    self.paths.get()
-  }
-}
-
-type Layout = (isize, Grid<bool>);
+type Layout = (Coord, Grid<bool>);
 
 fn parse(input: &str) -> Layout {
-  let start = input.find('S').expect("should have a starting position");
-  let grid = Grid::new(input.parse_char_grid().map_2d(|c| c == '^'));
-  (start as isize, grid)
+  let grid = Grid::try_parse(input, |c| matches!(c, '^' | '.' | 'S').then_some(c))
+    .expect("should be a valid manifold");
+  let start = grid.find('S').expect("should have a starting position");
+  (start, grid.map(|cell| *cell == '^'))
 }
 
 fn part_1((start, grid): Layout) -> u64 {
   let mut splits = 0;
-  let mut cells = vec![grid.cell((0, start))];
+  let mut cells = vec![grid.cell(start)];
   while !cells.is_empty() {
     let mut new_cells = vec![];
     for cell in cells {
@@ -70,25 +46,27 @@ fn part_1((start, grid): Layout) -> u64 {
 }
 
 fn part_2((start, grid): Layout) -> u64 {
-  let grid = grid.map(|c| QuantumNonsense::new(*c));
-
-  grid[(0, start)].increment_paths(1);
-
-  for cell in grid.cells().filter(|c| c.paths() > 0) {
-    if let Some(next) = cell.neighbour(Direction::S) {
-      if next.is_splitter {
-        for dir in [Direction::W, Direction::E] {
-          if let Some(neighbour) = next.neighbour(dir) {
-            neighbour.increment_paths(cell.paths());
-          }
-        }
+  let mut paths = grid.map(|_| 0);
+  paths[start] = 1;
+
+  paths.sweep(|mut cell| {
+    use Direction::*;
+    let count = *cell;
+    if let Some(next) = grid.cell(cell.coord).neighbour(S) {
+      let targets = if *next {
+        [SW, SE].as_slice()
       } else {
-        next.increment_paths(cell.paths());
+        [S].as_slice()
+      };
+      for &dir in targets {
+        if let Some(target) = cell.neighbour_mut(dir) {
+          *target += count;
+        }
       }
     }
-  }
+  });
 
-  grid.rows().last().unwrap().map(|c| c.paths()).sum()
+  paths.rows().last().unwrap().map(|c| *c).sum()
 }
 
 #[cfg(test)]
@@ -3,7 +3,7 @@ use std::{
   collections::{HashMap, HashSet},
 };
 
-use aoc::prelude::*;
+use aoc::{grid::Coord3, prelude::*};
 
 solution! {
   year: 2025,
@@ -13,14 +13,7 @@ solution! {
   part_2,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-struct Point {
-  x: u32,
-  y: u32,
-  z: u32,
-}
-
-fn sorted_edges(points: &[Point]) -> Vec<(Point, Point)> {
+fn sorted_edges(points: &[Coord3]) -> Vec<(Coord3, Coord3)> {
   let mut edges = Vec::with_capacity((points.len() * (points.len() + 1)) / 2);
   for (i, &p1) in points.iter().enumerate() {
     for &p2 in points.iter().skip(i + 1) {
@@ -28,14 +21,14 @@ fn sorted_edges(points: &[Point]) -> Vec<(Point, Point)> {
     }
   }
 
-  edges.sort_by_cached_key(|(p1, p2)| p1.distance_squared(p2));
+  edges.sort_by_cached_key(|(p1, p2)| p1.distance_squared(*p2));
   edges
 }
 
 struct JunctionGraph {
-  networks: Vec<HashSet<Point>>,
+  networks: Vec<HashSet<Coord3>>,
   nulled_networks: usize,
-  memberships: HashMap<Point, usize>,
+  memberships: HashMap<Coord3, usize>,
 }
 
 impl JunctionGraph {
@@ -55,7 +48,7 @@ impl JunctionGraph {
     self.networks.len() - self.nulled_networks
   }
 
-  fn add_connection(&mut self, p1: Point, p2: Point) {
+  fn add_connection(&mut self, p1: Coord3, p2: Coord3) {
     match (self.memberships.get(&p1), self.memberships.get(&p2)) {
       (Some(&net1), Some(&net2)) => {
         if net1 != net2 {
@@ -84,30 +77,18 @@ impl JunctionGraph {
   }
 }
 
-impl Point {
-  fn new(x: u32, y: u32, z: u32) -> Self {
-    Self { x, y, z }
-  }
-
-  fn distance_squared(&self, other: &Point) -> u64 {
-    (self.x.abs_diff(other.x) as u64).pow(2)
-      + (self.y.abs_diff(other.y) as u64).pow(2)
-      + (self.z.abs_diff(other.z) as u64).pow(2)
-  }
-}
-
-fn parse(input: &str) -> Vec<Point> {
+fn parse(input: &str) -> Vec<Coord3> {
   use parse::*;
 
   input.parse_lines(
-    separated_list1(tag(","), u32).map(|ns| match ns.as_slice() {
-      [x, y, z] => Point::new(*x, *y, *z),
+    separated_list1(tag(","), i64).map(|ns| match ns.as_slice() {
+      [x, y, z] => Coord3::new(*x as isize, *y as isize, *z as isize),
       _ => panic!("should be three values per row"),
     }),
   )
 }
 
-fn part_1(points: Vec<Point>, connections: usize) -> usize {
+fn part_1(points: Vec<Coord3>, connections: usize) -> usize {
   let mut graph = JunctionGraph::new();
   for &(p1, p2) in sorted_edges(&points).iter().take(connections) {
     graph.add_connection(p1, p2);
@@ -118,12 +99,12 @@ fn part_1(points: Vec<Point>, connections: usize) -> usize {
   networks.into_iter().take(3).map(|net| net.len()).product()
 }
 
-fn part_2(points: Vec<Point>) -> u32 {
+fn part_2(points: Vec<Coord3>) -> isize {
   let mut graph = JunctionGraph::new();
   for (p1, p2) in sorted_edges(&points) {
     graph.add_connection(p1, p2);
     if graph.total_points() == points.len() && graph.total_networks() == 1 {
-      return p1.x * p2.x;
+      return p1.x() * p2.x();
     }
   }
 
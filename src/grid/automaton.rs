@@ -0,0 +1,93 @@
+use super::{Grid, GridCell};
+
+/// How a cellular automaton applies its rule across the grid in each step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Update {
+  /// Every cell's new value is worked out from the grid as it was before the step.
+  Synchronous,
+  /// Cells are updated one at a time in row-major order, so later cells see the new values
+  /// of earlier ones.
+  InPlace,
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+  /// Replaces every cell's value with the result of `rule`, which sees the cell and (through
+  /// it) its neighbourhood. Returns how many cells changed.
+  pub fn step(&mut self, update: Update, rule: impl Fn(GridCell<T>) -> T) -> usize {
+    match update {
+      Update::Synchronous => {
+        let next = self.map(&rule);
+        let changes = self
+          .data
+          .iter()
+          .zip(&next.data)
+          .filter(|(old, new)| old != new)
+          .count();
+        *self = next;
+        changes
+      }
+      Update::InPlace => {
+        let mut changes = 0;
+        for coord in self.coords() {
+          let value = rule(self.cell(coord));
+          if self[coord] != value {
+            self[coord] = value;
+            changes += 1;
+          }
+        }
+        changes
+      }
+    }
+  }
+
+  /// Steps the automaton `steps` times, returning how many cells changed in each step.
+  pub fn run(
+    &mut self,
+    steps: usize,
+    update: Update,
+    rule: impl Fn(GridCell<T>) -> T,
+  ) -> Vec<usize> {
+    (0..steps).map(|_| self.step(update, &rule)).collect()
+  }
+
+  /// Steps the automaton until a step leaves every cell unchanged, returning how many cells
+  /// changed in each step before that. This never returns if the automaton doesn't settle.
+  pub fn run_until_stable(
+    &mut self,
+    update: Update,
+    rule: impl Fn(GridCell<T>) -> T,
+  ) -> Vec<usize> {
+    std::iter::repeat_with(|| self.step(update, &rule))
+      .take_while(|&changes| changes > 0)
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_automaton() {
+    let life = |cell: GridCell<bool>| {
+      let alive = cell.count_neighbours(|&alive| alive);
+      alive == 3 || (*cell && alive == 2)
+    };
+    let render = |grid: &Grid<bool>| grid.render(|&alive| if alive { '#' } else { '.' });
+
+    let mut blinker = Grid::parse(".....\n..#..\n..#..\n..#..\n.....", |c| c == '#');
+    assert_eq!(blinker.run(2, Update::Synchronous, life), [4, 4]);
+    assert_eq!(render(&blinker), ".....\n..#..\n..#..\n..#..\n.....\n");
+
+    // Spreading into empty cells finishes in one pass when later cells see earlier updates
+    let spread = |cell: GridCell<bool>| *cell || cell.cardinal_neighbours().any(|n| *n);
+    let mut synchronous = Grid::parse("#...", |c| c == '#');
+    let mut in_place = synchronous.clone();
+    assert_eq!(
+      synchronous.run_until_stable(Update::Synchronous, spread),
+      [1, 1, 1]
+    );
+    assert_eq!(in_place.run_until_stable(Update::InPlace, spread), [3]);
+    assert_eq!(synchronous, in_place);
+  }
+}
@@ -0,0 +1,348 @@
+use std::{
+  fmt::{Debug, Display, Formatter, Result as FmtResult},
+  ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, RangeInclusive},
+};
+
+use super::{Coord, Direction, Grid, GridCell, GridSource, render};
+
+pub type BitCell<'a> = GridCell<'a, bool, BitGrid>;
+
+/// A grid of booleans packed into `u64` words, one bit per cell. Whole-grid operations
+/// (shifting, combining grids, counting neighbours) work on 64 cells at a time, which makes
+/// Life-like simulations on large grids much faster than with a `Grid<bool>`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+  /// Each row is `words_per_row` words, with column `min_col` in the lowest bit of the
+  /// row's first word. Bits past the last column are always zero.
+  words: Vec<u64>,
+  words_per_row: usize,
+  min_row: isize,
+  min_col: isize,
+  height: usize,
+  width: usize,
+}
+
+impl BitGrid {
+  /// An empty (all false) grid of the given size, with its top-left corner at (0, 0).
+  pub fn new(height: usize, width: usize) -> Self {
+    let words_per_row = width.div_ceil(64);
+    Self {
+      words: vec![0; height * words_per_row],
+      words_per_row,
+      min_row: 0,
+      min_col: 0,
+      height,
+      width,
+    }
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn bounds(&self) -> (Coord, Coord) {
+    let min = Coord::from((self.min_row, self.min_col));
+    let max = min + Coord::from((self.height as isize - 1, self.width as isize - 1));
+    (min, max)
+  }
+
+  pub fn contains(&self, coord: Coord) -> bool {
+    self.row_range().contains(&coord.row) && self.col_range().contains(&coord.col)
+  }
+
+  pub fn get(&self, coord: impl Into<Coord>) -> bool {
+    let (word, bit) = self.bit_offset(coord.into());
+    self.words[word] & (1 << bit) != 0
+  }
+
+  pub fn set(&mut self, coord: impl Into<Coord>, value: bool) {
+    let (word, bit) = self.bit_offset(coord.into());
+    if value {
+      self.words[word] |= 1 << bit;
+    } else {
+      self.words[word] &= !(1 << bit);
+    }
+  }
+
+  pub fn cell(&self, coord: impl Into<Coord>) -> BitCell<'_> {
+    GridCell::new(coord.into(), self)
+  }
+
+  /// The number of cells that are set.
+  pub fn count_ones(&self) -> usize {
+    self
+      .words
+      .iter()
+      .map(|word| word.count_ones() as usize)
+      .sum()
+  }
+
+  /// The coords of every cell that is set, in row-major order.
+  pub fn ones(&self) -> impl Iterator<Item = Coord> + '_ {
+    let rows = self.row_range();
+    let cols = self.col_range();
+    rows
+      .flat_map(move |row| cols.clone().map(move |col| Coord { row, col }))
+      .filter(|&coord| self.get(coord))
+  }
+
+  /// Moves every set cell one step in a direction. Cells moved off the edge are lost, and
+  /// cells moved away from the opposite edge are left unset.
+  pub fn shift(&self, direction: Direction) -> Self {
+    let offset = direction.offset();
+    let mut shifted = self.blank();
+
+    let per_row = self.words_per_row;
+    for row in 0..self.height {
+      let Some(from) = row
+        .checked_add_signed(-offset.row)
+        .filter(|&r| r < self.height)
+      else {
+        continue;
+      };
+      let source = &self.words[from * per_row..(from + 1) * per_row];
+      let target = &mut shifted.words[row * per_row..(row + 1) * per_row];
+      for (i, word) in target.iter_mut().enumerate() {
+        *word = match offset.col {
+          0 => source[i],
+          // Moving east moves each bit to a higher column, carrying in from the previous word
+          1 => source[i] << 1 | i.checked_sub(1).map_or(0, |j| source[j] >> 63),
+          _ => source[i] >> 1 | source.get(i + 1).map_or(0, |next| next << 63),
+        };
+      }
+    }
+    shifted.clear_padding();
+    shifted
+  }
+
+  /// For every cell, how many of its eight neighbours are set.
+  pub fn neighbour_counts(&self) -> NeighbourCounts {
+    let mut planes = [(); 4].map(|_| self.blank());
+    for direction in Direction::all() {
+      // Add the shifted grid into the count, bit-plane by bit-plane, like a ripple-carry
+      // adder running on 64 cells at once
+      let mut carry = self.shift(direction).words;
+      for plane in &mut planes {
+        for (bits, carry) in plane.words.iter_mut().zip(&mut carry) {
+          let sum = *bits ^ *carry;
+          *carry &= *bits;
+          *bits = sum;
+        }
+      }
+    }
+    NeighbourCounts { planes }
+  }
+
+  pub fn to_grid(&self) -> Grid<bool> {
+    let (min, max) = self.bounds();
+    let mut grid = Grid::with_bounds(min, max, false);
+    for coord in self.ones() {
+      grid[coord] = true;
+    }
+    grid
+  }
+
+  pub fn render(&self, f: impl Fn(&bool) -> char) -> String {
+    render(self, f)
+  }
+
+  /// An empty grid with the same bounds as this one.
+  fn blank(&self) -> Self {
+    Self {
+      words: vec![0; self.words.len()],
+      ..*self
+    }
+  }
+
+  fn bit_offset(&self, coord: Coord) -> (usize, usize) {
+    assert!(self.contains(coord), "{coord:?} is outside the grid");
+    let row = (coord.row - self.min_row) as usize;
+    let col = (coord.col - self.min_col) as usize;
+    (row * self.words_per_row + col / 64, col % 64)
+  }
+
+  /// Unsets the unused bits after the last column, so they can't leak into later shifts or
+  /// counts.
+  fn clear_padding(&mut self) {
+    if self.width.is_multiple_of(64) {
+      return;
+    }
+    let mask = (1 << (self.width % 64)) - 1;
+    for row in self.words.chunks_mut(self.words_per_row) {
+      *row.last_mut().unwrap() &= mask;
+    }
+  }
+}
+
+/// The number of set neighbours of each cell of a `BitGrid`, stored as four bit-planes.
+pub struct NeighbourCounts {
+  planes: [BitGrid; 4],
+}
+
+impl NeighbourCounts {
+  pub fn get(&self, coord: impl Into<Coord>) -> u8 {
+    let coord = coord.into();
+    (0..4)
+      .map(|bit| (self.planes[bit].get(coord) as u8) << bit)
+      .sum()
+  }
+
+  /// The cells with exactly `count` neighbours set.
+  pub fn exactly(&self, count: u8) -> BitGrid {
+    let mut result = !&self.planes[0].blank();
+    for (bit, plane) in self.planes.iter().enumerate() {
+      if count & (1 << bit) != 0 {
+        result &= plane;
+      } else {
+        result &= &!plane;
+      }
+    }
+    result
+  }
+
+  /// The cells with at least `count` neighbours set.
+  pub fn at_least(&self, count: u8) -> BitGrid {
+    (count..=8).fold(self.planes[0].blank(), |result, count| {
+      result | &self.exactly(count)
+    })
+  }
+}
+
+impl GridSource<bool> for BitGrid {
+  fn contains(&self, coord: Coord) -> bool {
+    self.contains(coord)
+  }
+
+  fn value_at(&self, coord: Coord) -> &bool {
+    if self.get(coord) { &true } else { &false }
+  }
+
+  fn row_range(&self) -> RangeInclusive<isize> {
+    self.min_row..=self.min_row + self.height as isize - 1
+  }
+
+  fn col_range(&self) -> RangeInclusive<isize> {
+    self.min_col..=self.min_col + self.width as isize - 1
+  }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+  fn from(grid: &Grid<bool>) -> Self {
+    let (min, _) = grid.bounds();
+    let mut bits = Self {
+      min_row: min.row,
+      min_col: min.col,
+      ..Self::new(grid.height(), grid.width())
+    };
+    for cell in grid.cells().filter(|cell| **cell) {
+      bits.set(cell.coord, true);
+    }
+    bits
+  }
+}
+
+impl Not for &BitGrid {
+  type Output = BitGrid;
+
+  fn not(self) -> BitGrid {
+    let mut result = self.clone();
+    result.words.iter_mut().for_each(|word| *word = !*word);
+    result.clear_padding();
+    result
+  }
+}
+
+macro_rules! bitwise_ops {
+  ($($trait:ident $method:ident $assign_trait:ident $assign_method:ident $op:tt),*) => {$(
+    impl $assign_trait<&BitGrid> for BitGrid {
+      fn $assign_method(&mut self, other: &BitGrid) {
+        assert_eq!(self.bounds(), other.bounds(), "Grids must be the same size");
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+          *word $op *other;
+        }
+      }
+    }
+
+    impl $trait<&BitGrid> for BitGrid {
+      type Output = BitGrid;
+
+      fn $method(mut self, other: &BitGrid) -> BitGrid {
+        self.$assign_method(other);
+        self
+      }
+    }
+  )*};
+}
+
+bitwise_ops!(
+  BitAnd bitand BitAndAssign bitand_assign &=,
+  BitOr bitor BitOrAssign bitor_assign |=,
+  BitXor bitxor BitXorAssign bitxor_assign ^=
+);
+
+/// Displays set cells as `#` and unset cells as `.`.
+impl Display for BitGrid {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.write_str(&self.render(|&set| if set { '#' } else { '.' }))
+  }
+}
+
+impl Debug for BitGrid {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let rows = self.to_string();
+    f.debug_struct("BitGrid")
+      .field("rows", &self.row_range())
+      .field("cols", &self.col_range())
+      .field("cells", &rows.lines().collect::<Vec<_>>())
+      .finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_bit_grid() {
+    // Wide enough that neighbours cross word boundaries
+    let grid = Grid::new(
+      (0..5)
+        .map(|row| (0..130).map(|col| (row * 7 + col * 3) % 5 < 2).collect())
+        .collect(),
+    );
+    let bits = BitGrid::from(&grid);
+    assert_eq!(bits.to_grid(), grid);
+    assert_eq!(
+      bits.count_ones(),
+      grid.cells().filter(|cell| **cell).count()
+    );
+
+    let counts = bits.neighbour_counts();
+    for cell in grid.cells() {
+      assert_eq!(
+        counts.get(cell.coord) as usize,
+        cell.count_neighbours(|&b| b)
+      );
+    }
+    let crowded = counts.at_least(4);
+    for cell in grid.cells() {
+      assert_eq!(crowded.get(cell.coord), cell.count_neighbours(|&b| b) >= 4);
+    }
+
+    let glider = BitGrid::from(&Grid::parse(".#...\n..#..\n###..\n.....\n.....", |c| {
+      c == '#'
+    }));
+    let step = |life: &BitGrid| {
+      let counts = life.neighbour_counts();
+      counts.exactly(3) | &(counts.exactly(2) & life)
+    };
+    let moved = (0..4).fold(glider.clone(), |life, _| step(&life));
+    assert_eq!(moved, glider.shift(Direction::SE));
+    assert_eq!(moved.to_string(), ".....\n..#..\n...#.\n.###.\n.....\n");
+    assert_eq!(!&glider ^ &glider, !&BitGrid::new(5, 5));
+  }
+}
@@ -0,0 +1,476 @@
+use std::{
+  fmt::{Display, Formatter, Result as FmtResult},
+  ops::{Add, AddAssign, Deref, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+};
+
+use super::{Coord, Direction, Grid, ParseDirectionError};
+
+/// A position on a hex grid in axial coordinates: `q` increases towards the east (or
+/// south-east, on a flat-topped grid), and `r` increases towards the south-east (or south).
+/// The third cube coordinate, `s`, is implied by `q + r + s = 0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HexCoord {
+  pub q: isize,
+  pub r: isize,
+}
+
+impl From<(isize, isize)> for HexCoord {
+  fn from((q, r): (isize, isize)) -> Self {
+    HexCoord { q, r }
+  }
+}
+
+impl HexCoord {
+  pub const ORIGIN: HexCoord = HexCoord { q: 0, r: 0 };
+
+  /// The six unit steps, going anticlockwise from `+q`, which is also the order a ring is
+  /// walked in.
+  const STEPS: [HexCoord; 6] = [
+    HexCoord { q: 1, r: 0 },
+    HexCoord { q: 1, r: -1 },
+    HexCoord { q: 0, r: -1 },
+    HexCoord { q: -1, r: 0 },
+    HexCoord { q: -1, r: 1 },
+    HexCoord { q: 0, r: 1 },
+  ];
+
+  /// The third cube coordinate.
+  pub fn s(&self) -> isize {
+    -self.q - self.r
+  }
+
+  /// The number of steps needed to get from one hex to the other.
+  pub fn distance(&self, other: HexCoord) -> usize {
+    let diff = *self - other;
+    (diff.q.unsigned_abs() + diff.r.unsigned_abs() + diff.s().unsigned_abs()) / 2
+  }
+
+  /// The six hexes next to this one.
+  pub fn neighbours(&self) -> impl Iterator<Item = HexCoord> + use<> {
+    let centre = *self;
+    Self::STEPS.into_iter().map(move |step| centre + step)
+  }
+
+  /// The hexes exactly `radius` steps away, in order around the ring.
+  pub fn ring(&self, radius: usize) -> impl Iterator<Item = HexCoord> + use<> {
+    let mut current = *self + Self::STEPS[4] * radius as isize;
+    let sides = if radius == 0 { 0 } else { 6 };
+    let centre = (radius == 0).then_some(*self);
+    let edges = (0..sides).flat_map(move |side| (0..radius).map(move |_| Self::STEPS[side]));
+    centre.into_iter().chain(edges.map(move |step| {
+      let coord = current;
+      current += step;
+      coord
+    }))
+  }
+
+  /// The hexes up to `radius` steps away, starting with this one and working outwards ring
+  /// by ring.
+  pub fn spiral(&self, radius: usize) -> impl Iterator<Item = HexCoord> + use<> {
+    let centre = *self;
+    (0..=radius).flat_map(move |radius| centre.ring(radius))
+  }
+
+  /// Where this hex is stored in a `HexGrid`'s backing grid.
+  fn storage(&self) -> Coord {
+    Coord::from((self.r, self.q))
+  }
+}
+
+impl Add for HexCoord {
+  type Output = HexCoord;
+
+  fn add(self, other: HexCoord) -> HexCoord {
+    HexCoord {
+      q: self.q + other.q,
+      r: self.r + other.r,
+    }
+  }
+}
+
+impl Sub for HexCoord {
+  type Output = HexCoord;
+
+  fn sub(self, other: HexCoord) -> HexCoord {
+    HexCoord {
+      q: self.q - other.q,
+      r: self.r - other.r,
+    }
+  }
+}
+
+impl Neg for HexCoord {
+  type Output = HexCoord;
+
+  fn neg(self) -> HexCoord {
+    HexCoord {
+      q: -self.q,
+      r: -self.r,
+    }
+  }
+}
+
+impl Mul<isize> for HexCoord {
+  type Output = HexCoord;
+
+  fn mul(self, scale: isize) -> HexCoord {
+    HexCoord {
+      q: self.q * scale,
+      r: self.r * scale,
+    }
+  }
+}
+
+impl AddAssign for HexCoord {
+  fn add_assign(&mut self, other: HexCoord) {
+    *self = *self + other;
+  }
+}
+
+impl SubAssign for HexCoord {
+  fn sub_assign(&mut self, other: HexCoord) {
+    *self = *self - other;
+  }
+}
+
+/// Which way up the hexes are, which decides which six compass `Direction`s lead to
+/// neighbours: pointy-topped hexes have neighbours to the east and west, and flat-topped
+/// hexes have neighbours to the north and south.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HexOrientation {
+  Pointy,
+  Flat,
+}
+
+impl HexOrientation {
+  /// The six directions that lead to neighbours, clockwise from the north.
+  pub fn directions(&self) -> impl Iterator<Item = Direction> + use<> {
+    let orientation = *self;
+    Direction::all().filter(move |&direction| orientation.offset(direction).is_some())
+  }
+
+  /// The change in coords from taking one step in a direction, or `None` if there's no
+  /// neighbour that way (north and south on a pointy grid, east and west on a flat one).
+  pub fn offset(&self, direction: Direction) -> Option<HexCoord> {
+    use Direction::*;
+    let (q, r) = match (self, direction) {
+      (HexOrientation::Pointy, E) | (HexOrientation::Flat, SE) => (1, 0),
+      (_, NE) => (1, -1),
+      (HexOrientation::Pointy, NW) | (HexOrientation::Flat, N) => (0, -1),
+      (HexOrientation::Pointy, W) | (HexOrientation::Flat, NW) => (-1, 0),
+      (_, SW) => (-1, 1),
+      (HexOrientation::Pointy, SE) | (HexOrientation::Flat, S) => (0, 1),
+      _ => return None,
+    };
+    Some(HexCoord { q, r })
+  }
+
+  /// Reads a single compass direction like `ne` or `S`, which must lead to a neighbour with
+  /// this orientation. Other encodings of directions (arrows, or `U`/`D`/`L`/`R`) aren't
+  /// accepted.
+  pub fn parse_direction(&self, s: &str) -> Result<Direction, ParseDirectionError> {
+    Direction::all()
+      .find(|direction| direction.to_string().eq_ignore_ascii_case(s))
+      .filter(|&direction| self.offset(direction).is_some())
+      .ok_or_else(|| ParseDirectionError(s.to_string()))
+  }
+
+  /// Reads a path of directions, either separated by commas (`ne,ne,s`) or run together
+  /// (`esenee`).
+  pub fn parse_path(&self, s: &str) -> Result<Vec<Direction>, ParseDirectionError> {
+    let mut directions = Vec::new();
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+      let mut chars = rest.chars().map(|c| c.to_ascii_lowercase());
+      let first = chars.next().unwrap();
+      let two_letter = matches!(first, 'n' | 's') && matches!(chars.next(), Some('e' | 'w'));
+      let (direction, tail) = rest.split_at(if two_letter { 2 } else { first.len_utf8() });
+      directions.push(self.parse_direction(direction)?);
+      rest = tail.trim_start_matches([',', ' ']);
+    }
+    Ok(directions)
+  }
+}
+
+/// A hexagon-shaped grid of hexes, centred on the origin. It's stored in a `Grid` (with `r`
+/// as the row and `q` as the column), whose corners are left empty.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexGrid<T> {
+  cells: Grid<Option<T>>,
+  radius: usize,
+  orientation: HexOrientation,
+}
+
+impl<T> HexGrid<T> {
+  /// A grid of every hex up to `radius` steps from the origin, each set to `fill`.
+  pub fn new(orientation: HexOrientation, radius: usize, fill: T) -> Self
+  where
+    T: Clone,
+  {
+    let radius_coord = Coord::from((radius as isize, radius as isize));
+    let mut cells = Grid::with_bounds(-radius_coord, radius_coord, None);
+    for coord in HexCoord::ORIGIN.spiral(radius) {
+      cells[coord.storage()] = Some(fill.clone());
+    }
+    Self {
+      cells,
+      radius,
+      orientation,
+    }
+  }
+
+  pub fn orientation(&self) -> HexOrientation {
+    self.orientation
+  }
+
+  pub fn radius(&self) -> usize {
+    self.radius
+  }
+
+  pub fn contains(&self, coord: HexCoord) -> bool {
+    coord.distance(HexCoord::ORIGIN) <= self.radius
+  }
+
+  /// Grows the grid (by whole rings) until it includes the coord, filling the new hexes
+  /// with `fill`.
+  pub fn expand_to_include(&mut self, coord: impl Into<HexCoord>, fill: T)
+  where
+    T: Clone,
+  {
+    let distance = coord.into().distance(HexCoord::ORIGIN);
+    if distance > self.radius {
+      self.cells.pad(distance - self.radius, None);
+      self.radius = distance;
+      for coord in HexCoord::ORIGIN.spiral(distance) {
+        self.cells[coord.storage()].get_or_insert_with(|| fill.clone());
+      }
+    }
+  }
+
+  pub fn cell(&self, coord: impl Into<HexCoord>) -> HexCell<'_, T> {
+    let coord = coord.into();
+    assert!(self.contains(coord), "{coord:?} is outside the grid");
+    HexCell { coord, grid: self }
+  }
+
+  /// Every hex in the grid, working outwards from the origin ring by ring.
+  pub fn coords(&self) -> impl Iterator<Item = HexCoord> + use<T> {
+    HexCoord::ORIGIN.spiral(self.radius)
+  }
+
+  pub fn cells(&self) -> impl Iterator<Item = HexCell<'_, T>> {
+    self.coords().map(|coord| self.cell(coord))
+  }
+
+  pub fn map<U>(&self, f: impl Fn(HexCell<T>) -> U) -> HexGrid<U> {
+    HexGrid {
+      cells: self.cells.map(|cell| {
+        let coord = HexCoord::from((cell.coord.col, cell.coord.row));
+        cell.as_ref().map(|_| f(self.cell(coord)))
+      }),
+      radius: self.radius,
+      orientation: self.orientation,
+    }
+  }
+
+  /// Renders the grid as text, one character per hex with the rows (or, for a flat grid,
+  /// columns) staggered so that neighbours are next to each other.
+  pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+    // Lay the hexes out in half-hex units, which puts every hex on a whole position
+    let position = |coord: HexCoord| match self.orientation {
+      HexOrientation::Pointy => (coord.r, 2 * coord.q + coord.r),
+      HexOrientation::Flat => (2 * coord.r + coord.q, 2 * coord.q),
+    };
+    let radius = self.radius as isize;
+    let mut text = Grid::with_bounds((-2 * radius, -2 * radius), (2 * radius, 2 * radius), ' ');
+    for cell in self.cells() {
+      text[position(cell.coord)] = f(cell.value());
+    }
+    let mut rendered = String::new();
+    for row in text.rows() {
+      let line = row.map(|cell| *cell).collect::<String>();
+      let line = line.trim_end();
+      if !line.is_empty() {
+        rendered.push_str(line);
+        rendered.push('\n');
+      }
+    }
+    rendered
+  }
+}
+
+impl<T, C: Into<HexCoord>> Index<C> for HexGrid<T> {
+  type Output = T;
+
+  fn index(&self, coord: C) -> &Self::Output {
+    let coord = coord.into();
+    assert!(self.contains(coord), "{coord:?} is outside the grid");
+    self.cells[coord.storage()].as_ref().unwrap()
+  }
+}
+
+impl<T, C: Into<HexCoord>> IndexMut<C> for HexGrid<T> {
+  fn index_mut(&mut self, coord: C) -> &mut Self::Output {
+    let coord = coord.into();
+    assert!(self.contains(coord), "{coord:?} is outside the grid");
+    self.cells[coord.storage()].as_mut().unwrap()
+  }
+}
+
+/// Displays each hex using its own `Display` impl, which is expected to produce exactly
+/// one character.
+impl<T: Display> Display for HexGrid<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let rendered = self.render(|value| value.to_string().chars().next().unwrap_or(' '));
+    f.write_str(&rendered)
+  }
+}
+
+/// A handle on one hex of a `HexGrid`, which can look around at its neighbours in the same
+/// way as a `GridCell`.
+pub struct HexCell<'a, T> {
+  pub coord: HexCoord,
+  grid: &'a HexGrid<T>,
+}
+
+impl<T> Copy for HexCell<'_, T> {}
+impl<T> Clone for HexCell<'_, T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<'a, T> HexCell<'a, T> {
+  pub fn neighbour(&self, direction: Direction) -> Option<HexCell<'a, T>> {
+    let coord = self.coord + self.grid.orientation.offset(direction)?;
+    self.grid.contains(coord).then_some(HexCell {
+      coord,
+      grid: self.grid,
+    })
+  }
+
+  /// The neighbouring hexes that are within the grid.
+  pub fn neighbours(&self) -> impl Iterator<Item = HexCell<'a, T>> + use<'a, T> {
+    let cell = *self;
+    self
+      .grid
+      .orientation
+      .directions()
+      .filter_map(move |direction| cell.neighbour(direction))
+  }
+
+  /// How many of the neighbouring hexes have a value matching the predicate.
+  pub fn count_neighbours(&self, predicate: impl Fn(&T) -> bool) -> usize {
+    self.neighbours().filter(|cell| predicate(cell)).count()
+  }
+
+  /// The hexes passed through by repeatedly stepping in a direction, stopping at the edge
+  /// of the grid.
+  pub fn travel(&self, direction: Direction) -> impl Iterator<Item = HexCell<'a, T>> + use<'a, T> {
+    let mut current = *self;
+    std::iter::from_fn(move || {
+      current = current.neighbour(direction)?;
+      Some(current)
+    })
+  }
+
+  pub fn value(&self) -> &'a T {
+    &self.grid[self.coord]
+  }
+}
+
+impl<T> Deref for HexCell<'_, T> {
+  type Target = T;
+
+  fn deref(&self) -> &Self::Target {
+    self.value()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn walk(orientation: HexOrientation, path: &[Direction]) -> HexCoord {
+    path.iter().fold(HexCoord::ORIGIN, |coord, &d| {
+      coord + orientation.offset(d).unwrap()
+    })
+  }
+
+  #[test]
+  fn test_rings_and_spirals() {
+    let origin = HexCoord::ORIGIN;
+    assert_eq!(origin.ring(0).collect::<Vec<_>>(), [origin]);
+    assert_eq!(origin.ring(2).count(), 12);
+    assert!(origin.ring(2).all(|coord| coord.distance(origin) == 2));
+    assert_eq!(origin.spiral(2).count(), 19);
+  }
+
+  #[test]
+  fn test_path_parsing() {
+    let pointy = HexOrientation::Pointy;
+    let path = pointy.parse_path("esenee").unwrap();
+    assert_eq!(
+      path,
+      [Direction::E, Direction::SE, Direction::NE, Direction::E]
+    );
+    assert_eq!(walk(pointy, &path).distance(HexCoord::ORIGIN), 3);
+    assert!(pointy.parse_path("n").is_err());
+    assert!(pointy.parse_path("é").is_err());
+    assert!(pointy.parse_path("r<").is_err());
+    assert!(pointy.parse_path("e>").is_err());
+
+    let flat = HexOrientation::Flat;
+    let path = flat.parse_path("se,sw,se,sw,sw").unwrap();
+    assert_eq!(walk(flat, &path).distance(HexCoord::ORIGIN), 3);
+    assert_eq!(flat.parse_direction("S"), Ok(Direction::S));
+    assert_eq!(flat.parse_direction("nW"), Ok(Direction::NW));
+    assert!(flat.parse_path("u").is_err());
+    assert!(flat.parse_direction("^").is_err());
+  }
+
+  #[test]
+  fn test_neighbours() {
+    let mut grid = HexGrid::new(HexOrientation::Pointy, 1, '.');
+    grid[(1, 0)] = 'e';
+    grid[(-1, 1)] = 'w';
+    let centre = grid.cell(HexCoord::ORIGIN);
+    assert_eq!(centre.neighbours().count(), 6);
+    assert_eq!(centre.count_neighbours(|&c| c != '.'), 2);
+    assert_eq!(*centre.neighbour(Direction::SW).unwrap(), 'w');
+    assert!(centre.neighbour(Direction::N).is_none());
+    assert_eq!(centre.travel(Direction::E).count(), 1);
+
+    let flat_grid = HexGrid::new(HexOrientation::Flat, 1, 'o');
+    assert_eq!(flat_grid.cell(HexCoord::ORIGIN).neighbours().count(), 6);
+  }
+
+  #[test]
+  fn test_render() {
+    let mut grid = HexGrid::new(HexOrientation::Pointy, 1, '.');
+    grid[(1, 0)] = 'e';
+    grid[(-1, 1)] = 'w';
+    assert_eq!(grid.to_string(), " . .\n. . e\n w .\n");
+
+    let flat_grid = HexGrid::new(HexOrientation::Flat, 1, 'o');
+    assert_eq!(flat_grid.to_string(), "  o\no   o\n  o\no   o\n  o\n");
+  }
+
+  #[test]
+  fn test_expand_and_map() {
+    let mut grid = HexGrid::new(HexOrientation::Pointy, 1, '.');
+    grid[(1, 0)] = 'e';
+    grid.expand_to_include((0, -2), '.');
+    assert_eq!(grid.radius(), 2);
+    assert_eq!(grid.cells().count(), 19);
+    assert_eq!(grid[(1, 0)], 'e');
+
+    let calls = std::cell::Cell::new(0);
+    let marked = grid.map(|cell| {
+      calls.set(calls.get() + 1);
+      *cell != '.'
+    });
+    assert_eq!(calls.get(), 19);
+    assert!(marked[(1, 0)] && !marked[(0, -2)]);
+  }
+}
@@ -0,0 +1,190 @@
+use std::{
+  fs,
+  io::{self, Write},
+  path::{Path, PathBuf},
+};
+
+use super::Grid;
+
+/// A colour, as red, green and blue intensities.
+pub type Rgb = [u8; 3];
+
+/// A picture of a grid, for grids that are too big to make sense of as text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+  width: usize,
+  height: usize,
+  pixels: Vec<Rgb>,
+}
+
+impl Image {
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  /// The colour of the pixel at a position, counting from the top left.
+  pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+    assert!(x < self.width && y < self.height);
+    self.pixels[y * self.width + x]
+  }
+
+  /// Encodes the image as a binary PPM, which most image viewers can open.
+  pub fn to_ppm(&self) -> Vec<u8> {
+    let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+    ppm.extend(self.pixels.iter().flatten());
+    ppm
+  }
+
+  /// Saves the image to a file, as a PNG if the path ends in `.png` (which needs the `png`
+  /// feature) or as a PPM otherwise.
+  pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|extension| extension == "png") {
+      self.save_png(path)
+    } else {
+      fs::File::create(path)?.write_all(&self.to_ppm())
+    }
+  }
+
+  #[cfg(feature = "png")]
+  fn save_png(&self, path: &Path) -> io::Result<()> {
+    let file = io::BufWriter::new(fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, self.width as u32, self.height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    let data = self.pixels.iter().flatten().copied().collect::<Vec<_>>();
+    writer.write_image_data(&data).map_err(io::Error::other)
+  }
+
+  #[cfg(not(feature = "png"))]
+  fn save_png(&self, _: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "Saving PNGs needs the `png` feature; save as .ppm instead",
+    ))
+  }
+}
+
+impl<T> Grid<T> {
+  /// Draws the grid as an image, with each cell as a `scale` by `scale` square of the
+  /// colour given by `colour`.
+  pub fn to_image(&self, scale: usize, colour: impl Fn(&T) -> Rgb) -> Image {
+    assert!(scale > 0);
+    let (width, height) = (self.width() * scale, self.height() * scale);
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in self.rows() {
+      let row = row
+        .flat_map(|cell| std::iter::repeat_n(colour(cell.value()), scale))
+        .collect::<Vec<_>>();
+      for _ in 0..scale {
+        pixels.extend_from_slice(&row);
+      }
+    }
+    Image {
+      width,
+      height,
+      pixels,
+    }
+  }
+}
+
+/// Saves a sequence of images as numbered files in a directory (`frame_00000.ppm`, and so
+/// on), so that a simulation can be stepped through in an image viewer or stitched into an
+/// animation.
+pub struct Frames {
+  dir: PathBuf,
+  extension: &'static str,
+  count: usize,
+}
+
+impl Frames {
+  /// Saves frames as PPMs in a directory, which is created if it doesn't exist.
+  pub fn ppm(dir: impl Into<PathBuf>) -> io::Result<Self> {
+    Self::new(dir.into(), "ppm")
+  }
+
+  /// Saves frames as PNGs in a directory, which is created if it doesn't exist.
+  #[cfg(feature = "png")]
+  pub fn png(dir: impl Into<PathBuf>) -> io::Result<Self> {
+    Self::new(dir.into(), "png")
+  }
+
+  fn new(dir: PathBuf, extension: &'static str) -> io::Result<Self> {
+    fs::create_dir_all(&dir)?;
+    Ok(Self {
+      dir,
+      extension,
+      count: 0,
+    })
+  }
+
+  /// Saves the next frame, returning the path it was saved to.
+  pub fn push(&mut self, image: &Image) -> io::Result<PathBuf> {
+    let name = format!("frame_{:05}.{}", self.count, self.extension);
+    let path = self.dir.join(name);
+    image.save(&path)?;
+    self.count += 1;
+    Ok(path)
+  }
+
+  /// How many frames have been saved so far.
+  pub fn len(&self) -> usize {
+    self.count
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.count == 0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_images() {
+    let grid = Grid::parse("#.\n.#", |c| c == '#');
+    let image = grid.to_image(2, |&on| if on { [255, 0, 0] } else { [0, 0, 0] });
+    assert_eq!((image.width(), image.height()), (4, 4));
+    assert_eq!(image.pixel(1, 1), [255, 0, 0]);
+    assert_eq!(image.pixel(2, 1), [0, 0, 0]);
+    assert_eq!(image.pixel(3, 3), [255, 0, 0]);
+
+    let ppm = image.to_ppm();
+    assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+    assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+
+    let dir = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+    let mut frames = Frames::ppm(&dir).unwrap();
+    frames.push(&image).unwrap();
+    let path = frames.push(&image).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert!(path.ends_with("frame_00001.ppm"));
+    assert_eq!(fs::read(&path).unwrap(), ppm);
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[cfg(feature = "png")]
+  #[test]
+  fn test_save_png() {
+    let grid = Grid::parse("#.\n.#", |c| c == '#');
+    let image = grid.to_image(3, |&on| if on { [0, 255, 0] } else { [0, 0, 0] });
+    let path = std::env::temp_dir().join(format!("aoc-image-{}.png", std::process::id()));
+    image.save(&path).unwrap();
+    let png = fs::read(&path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    let mut reader = png::Decoder::new(io::Cursor::new(png)).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!((frame.width, frame.height), (6, 6));
+    assert_eq!(pixels[..3], [0, 255, 0]);
+    assert_eq!(pixels[3 * 3..3 * 4], [0, 0, 0]);
+  }
+}
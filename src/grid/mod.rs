@@ -0,0 +1,1239 @@
+mod automaton;
+mod bits;
+mod hex;
+mod image;
+mod rays;
+mod regions;
+mod search;
+mod space;
+mod sparse;
+mod view;
+mod wrapping;
+
+pub use automaton::*;
+pub use bits::*;
+pub use hex::*;
+pub use image::*;
+pub use regions::*;
+pub use search::*;
+pub use space::*;
+pub use sparse::*;
+pub use view::*;
+pub use wrapping::*;
+
+use std::{
+  error::Error,
+  fmt::{Debug, Display, Formatter, Result as FmtResult},
+  hash::Hash,
+  marker::PhantomData,
+  ops::{
+    Add, AddAssign, Deref, DerefMut, Index, IndexMut, Mul, Neg, RangeInclusive, Sub, SubAssign,
+  },
+  str::FromStr,
+};
+
+/// A position on a grid, ordered by row and then by column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coord {
+  pub row: isize,
+  pub col: isize,
+}
+
+impl From<(isize, isize)> for Coord {
+  fn from((row, col): (isize, isize)) -> Self {
+    Coord { row, col }
+  }
+}
+
+impl From<Direction> for Coord {
+  fn from(direction: Direction) -> Self {
+    direction.offset()
+  }
+}
+
+impl Coord {
+  pub const ORIGIN: Coord = Coord { row: 0, col: 0 };
+
+  pub fn translate(&self, direction: impl Into<Direction>) -> Coord {
+    *self + direction.into().offset()
+  }
+
+  pub fn manhattan_distance(&self, other: Coord) -> usize {
+    self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+  }
+
+  /// The number of king's moves (including diagonals) needed to get from one coord to the
+  /// other.
+  pub fn chebyshev_distance(&self, other: Coord) -> usize {
+    self
+      .row
+      .abs_diff(other.row)
+      .max(self.col.abs_diff(other.col))
+  }
+
+  /// Clamps each component to -1, 0 or 1, giving a single step in the same general
+  /// direction as this vector.
+  pub fn signum(&self) -> Coord {
+    Coord {
+      row: self.row.signum(),
+      col: self.col.signum(),
+    }
+  }
+}
+
+impl Add for Coord {
+  type Output = Coord;
+
+  fn add(self, other: Coord) -> Coord {
+    Coord {
+      row: self.row + other.row,
+      col: self.col + other.col,
+    }
+  }
+}
+
+impl Sub for Coord {
+  type Output = Coord;
+
+  fn sub(self, other: Coord) -> Coord {
+    Coord {
+      row: self.row - other.row,
+      col: self.col - other.col,
+    }
+  }
+}
+
+impl Neg for Coord {
+  type Output = Coord;
+
+  fn neg(self) -> Coord {
+    Coord {
+      row: -self.row,
+      col: -self.col,
+    }
+  }
+}
+
+impl Mul<isize> for Coord {
+  type Output = Coord;
+
+  fn mul(self, scale: isize) -> Coord {
+    Coord {
+      row: self.row * scale,
+      col: self.col * scale,
+    }
+  }
+}
+
+impl AddAssign for Coord {
+  fn add_assign(&mut self, other: Coord) {
+    *self = *self + other;
+  }
+}
+
+impl SubAssign for Coord {
+  fn sub_assign(&mut self, other: Coord) {
+    *self = *self - other;
+  }
+}
+
+/// One of the eight compass directions, in clockwise order starting from north (up).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+  N,
+  NE,
+  E,
+  SE,
+  S,
+  SW,
+  W,
+  NW,
+}
+
+impl Direction {
+  const CLOCKWISE: [Direction; 8] = {
+    use Direction::*;
+    [N, NE, E, SE, S, SW, W, NW]
+  };
+
+  pub fn all() -> impl Iterator<Item = Direction> {
+    Self::CLOCKWISE.into_iter()
+  }
+
+  pub fn cardinals() -> impl Iterator<Item = Direction> {
+    use Direction::*;
+    [N, E, S, W].into_iter()
+  }
+
+  pub fn diagonals() -> impl Iterator<Item = Direction> {
+    use Direction::*;
+    [NE, SE, SW, NW].into_iter()
+  }
+
+  pub fn is_diagonal(&self) -> bool {
+    matches!(
+      self,
+      Direction::NE | Direction::SE | Direction::SW | Direction::NW
+    )
+  }
+
+  pub fn opposite(&self) -> Direction {
+    self.rotate(4)
+  }
+
+  /// Turns 90° counterclockwise.
+  pub fn turn_left(&self) -> Direction {
+    self.rotate(-2)
+  }
+
+  /// Turns 90° clockwise.
+  pub fn turn_right(&self) -> Direction {
+    self.rotate(2)
+  }
+
+  /// Turns 45° counterclockwise.
+  pub fn turn_left_45(&self) -> Direction {
+    self.rotate(-1)
+  }
+
+  /// Turns 45° clockwise.
+  pub fn turn_right_45(&self) -> Direction {
+    self.rotate(1)
+  }
+
+  /// Rotates clockwise by the given number of 45° steps.
+  fn rotate(&self, steps: isize) -> Direction {
+    Self::CLOCKWISE[(*self as isize + steps).rem_euclid(8) as usize]
+  }
+
+  /// The arrow (`^`, `>`, `v` or `<`) for a cardinal direction.
+  pub fn arrow(&self) -> Option<char> {
+    match self {
+      Direction::N => Some('^'),
+      Direction::E => Some('>'),
+      Direction::S => Some('v'),
+      Direction::W => Some('<'),
+      _ => None,
+    }
+  }
+
+  /// The letter (`U`, `R`, `D` or `L`) for a cardinal direction.
+  pub fn udlr(&self) -> Option<char> {
+    match self {
+      Direction::N => Some('U'),
+      Direction::E => Some('R'),
+      Direction::S => Some('D'),
+      Direction::W => Some('L'),
+      _ => None,
+    }
+  }
+
+  /// The change in coordinates from taking one step in this direction.
+  pub fn offset(&self) -> Coord {
+    let (row, col) = match self {
+      Direction::N => (-1, 0),
+      Direction::NE => (-1, 1),
+      Direction::E => (0, 1),
+      Direction::SE => (1, 1),
+      Direction::S => (1, 0),
+      Direction::SW => (1, -1),
+      Direction::W => (0, -1),
+      Direction::NW => (-1, -1),
+    };
+    Coord { row, col }
+  }
+}
+
+impl Display for Direction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    write!(f, "{self:?}")
+  }
+}
+
+/// Reads a direction from any of the usual single-character encodings: arrows (`^>v<`),
+/// compass points (`NESW`), or up/down/left/right (`UDLR`). Since `L` and `R` are only
+/// ever horizontal, this also covers left/right dials like day 1's.
+impl TryFrom<char> for Direction {
+  type Error = ParseDirectionError;
+
+  fn try_from(c: char) -> Result<Self, Self::Error> {
+    match c {
+      '^' | 'N' | 'U' => Ok(Direction::N),
+      '>' | 'E' | 'R' => Ok(Direction::E),
+      'v' | 'S' | 'D' => Ok(Direction::S),
+      '<' | 'W' | 'L' => Ok(Direction::W),
+      _ => Err(ParseDirectionError(c.to_string())),
+    }
+  }
+}
+
+/// Reads a direction from a single-character encoding (see `TryFrom<char>`) or a two-letter
+/// compass point like `NE`.
+impl FromStr for Direction {
+  type Err = ParseDirectionError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+      (Some(c), None, _) => Direction::try_from(c),
+      (Some(_), Some(_), None) => Direction::all()
+        .find(|d| d.to_string() == s)
+        .ok_or_else(|| ParseDirectionError(s.to_string())),
+      _ => Err(ParseDirectionError(s.to_string())),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDirectionError(pub String);
+
+impl Display for ParseDirectionError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    write!(f, "Invalid direction: {:?}", self.0)
+  }
+}
+
+impl Error for ParseDirectionError {}
+
+/// Storage that `GridCell`s can look up values in, which lets dense and sparse grids share
+/// the same cell-handle API.
+pub trait GridSource<T> {
+  /// Whether the coord lies within the grid's bounds.
+  fn contains(&self, coord: Coord) -> bool;
+
+  /// The value at a coord within the grid's bounds.
+  fn value_at(&self, coord: Coord) -> &T;
+
+  /// Where a step to the given coord lands, or `None` if it's off the grid. Grids whose
+  /// edges wrap around map coords outside their bounds back inside.
+  fn resolve(&self, coord: Coord) -> Option<Coord> {
+    self.contains(coord).then_some(coord)
+  }
+
+  fn row_range(&self) -> RangeInclusive<isize>;
+
+  fn col_range(&self) -> RangeInclusive<isize>;
+}
+
+/// A handle on one cell of a grid (by default a dense `Grid`), which can be used to look
+/// around at the cells near it.
+pub struct GridCell<'a, T, G = Grid<T>> {
+  pub coord: Coord,
+  source: &'a G,
+  values: PhantomData<&'a T>,
+}
+
+impl<'a, T, G> GridCell<'a, T, G> {
+  fn new(coord: Coord, source: &'a G) -> Self {
+    GridCell {
+      coord,
+      source,
+      values: PhantomData,
+    }
+  }
+}
+
+impl<T, G> Copy for GridCell<'_, T, G> {}
+impl<T, G> Clone for GridCell<'_, T, G> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T, G> Eq for GridCell<'_, T, G> {}
+impl<T, G> PartialEq for GridCell<'_, T, G> {
+  fn eq(&self, other: &Self) -> bool {
+    self.coord == other.coord && std::ptr::eq(self.source, other.source)
+  }
+}
+
+impl<T, G> Hash for GridCell<'_, T, G> {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    self.coord.hash(state);
+    std::ptr::hash(self.source, state);
+  }
+}
+
+impl<'a, T, G: GridSource<T>> GridCell<'a, T, G> {
+  pub fn neighbour(&self, direction: Direction) -> Option<GridCell<'a, T, G>> {
+    self.offset(direction)
+  }
+
+  /// The cell at the given offset from this one, if it's within the grid.
+  pub fn offset(&self, offset: impl Into<Coord>) -> Option<GridCell<'a, T, G>> {
+    let new_coord = self.source.resolve(self.coord + offset.into())?;
+    Some(GridCell::new(new_coord, self.source))
+  }
+
+  /// All eight surrounding cells that are within the grid.
+  pub fn neighbours(&self) -> impl Iterator<Item = GridCell<'a, T, G>> + use<'a, T, G> {
+    self.neighbours_in(Direction::all())
+  }
+
+  /// The (up to) four cells directly above, below, left and right of this one.
+  pub fn cardinal_neighbours(&self) -> impl Iterator<Item = GridCell<'a, T, G>> + use<'a, T, G> {
+    self.neighbours_in(Direction::cardinals())
+  }
+
+  /// The cells at each offset in the stencil (which may be a set of `Direction`s, or
+  /// arbitrary `Coord` offsets) that are within the grid.
+  pub fn neighbours_in<S>(
+    &self,
+    stencil: S,
+  ) -> impl Iterator<Item = GridCell<'a, T, G>> + use<'a, T, G, S>
+  where
+    S: IntoIterator,
+    S::Item: Into<Coord>,
+  {
+    let cell = *self;
+    stencil
+      .into_iter()
+      .filter_map(move |offset| cell.offset(offset))
+  }
+
+  /// How many of the eight surrounding cells have a value matching the predicate.
+  pub fn count_neighbours(&self, predicate: impl Fn(&T) -> bool) -> usize {
+    self.neighbours().filter(|cell| predicate(cell)).count()
+  }
+
+  /// The cells passed through by repeatedly stepping in a direction, stopping at the edge
+  /// of the grid (the bounding box, for a sparse grid) or, on a grid that wraps, on arriving
+  /// back at this cell.
+  pub fn travel(
+    &self,
+    direction: impl Into<Direction>,
+  ) -> impl Iterator<Item = GridCell<'a, T, G>> {
+    let start = self.coord;
+    let mut current = *self;
+    let direction = direction.into();
+    std::iter::from_fn(move || {
+      let next = current.neighbour(direction)?;
+      if next.coord == start || !next.source.contains(next.coord) {
+        return None;
+      }
+      current = next;
+      Some(current)
+    })
+  }
+
+  pub fn value(&self) -> &'a T {
+    self.source.value_at(self.coord)
+  }
+}
+
+impl<T, G: GridSource<T>> Deref for GridCell<'_, T, G> {
+  type Target = T;
+
+  fn deref(&self) -> &Self::Target {
+    self.value()
+  }
+}
+
+/// A handle on one cell of a grid that allows changing its value and its neighbours'.
+pub struct GridCellMut<'a, T> {
+  pub coord: Coord,
+  source: &'a mut Grid<T>,
+}
+
+impl<'a, T> GridCellMut<'a, T> {
+  /// A read-only handle on this cell, for looking at its surroundings.
+  pub fn as_cell(&self) -> GridCell<'_, T> {
+    GridCell::new(self.coord, self.source)
+  }
+
+  pub fn neighbour(&self, direction: Direction) -> Option<GridCell<'_, T>> {
+    self.as_cell().neighbour(direction)
+  }
+
+  pub fn neighbour_mut(&mut self, direction: Direction) -> Option<&mut T> {
+    let coord = self.coord.translate(direction);
+    if self.source.contains(coord) {
+      Some(&mut self.source[coord])
+    } else {
+      None
+    }
+  }
+
+  /// Moves this handle to the neighbouring cell, if there is one in that direction.
+  pub fn into_neighbour(self, direction: Direction) -> Option<GridCellMut<'a, T>> {
+    let coord = self.coord.translate(direction);
+    if self.source.contains(coord) {
+      Some(GridCellMut {
+        coord,
+        source: self.source,
+      })
+    } else {
+      None
+    }
+  }
+
+  pub fn value(&self) -> &T {
+    &self.source[self.coord]
+  }
+
+  pub fn value_mut(&mut self) -> &mut T {
+    &mut self.source[self.coord]
+  }
+}
+
+impl<T> Deref for GridCellMut<'_, T> {
+  type Target = T;
+
+  fn deref(&self) -> &Self::Target {
+    self.value()
+  }
+}
+
+impl<T> DerefMut for GridCellMut<'_, T> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self.value_mut()
+  }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+  data: Vec<T>,
+  min_row: isize,
+  max_row: isize,
+  min_col: isize,
+  max_col: isize,
+}
+
+impl<T> Grid<T> {
+  pub fn new(cells: Vec<Vec<T>>) -> Self {
+    let rows = cells.len();
+    assert!(rows > 0);
+    let cols = cells[0].len();
+    let mut data = Vec::with_capacity(rows * cols);
+    for row in cells {
+      assert!(row.len() == cols);
+      data.extend(row)
+    }
+    Self {
+      data,
+      min_row: 0,
+      max_row: rows as isize - 1,
+      min_col: 0,
+      max_col: cols as isize - 1,
+    }
+  }
+
+  /// Creates a grid covering every coord from `min` to `max` (inclusive), which may be
+  /// negative, with every cell set to `fill`.
+  pub fn with_bounds(min: impl Into<Coord>, max: impl Into<Coord>, fill: T) -> Self
+  where
+    T: Clone,
+  {
+    let (min, max) = (min.into(), max.into());
+    assert!(min.row <= max.row && min.col <= max.col);
+    let cells = (max.row - min.row + 1) as usize * (max.col - min.col + 1) as usize;
+    Self {
+      data: vec![fill; cells],
+      min_row: min.row,
+      max_row: max.row,
+      min_col: min.col,
+      max_col: max.col,
+    }
+  }
+
+  /// Grows the grid as needed so that it contains the given coord, filling any new cells
+  /// with `fill`.
+  pub fn expand_to_include(&mut self, coord: impl Into<Coord>, fill: T)
+  where
+    T: Clone,
+  {
+    let coord = coord.into();
+    if !self.contains(coord) {
+      let (min, max) = self.bounds();
+      let min = Coord::from((min.row.min(coord.row), min.col.min(coord.col)));
+      let max = Coord::from((max.row.max(coord.row), max.col.max(coord.col)));
+      self.grow_to(min, max, fill);
+    }
+  }
+
+  /// Adds `n` rows or columns of cells set to `fill` on every side of the grid.
+  pub fn pad(&mut self, n: usize, fill: T)
+  where
+    T: Clone,
+  {
+    let (min, max) = self.bounds();
+    let n = n as isize;
+    self.grow_to(min - Coord::from((n, n)), max + Coord::from((n, n)), fill);
+  }
+
+  /// Inserts a row of values so that it becomes row `row`, moving that row and every row
+  /// below it down by one. Passing one past the last row appends a row at the bottom.
+  pub fn insert_row(&mut self, row: isize, values: impl IntoIterator<Item = T>) {
+    assert!((self.min_row..=self.max_row + 1).contains(&row));
+    let values = values.into_iter().collect::<Vec<_>>();
+    assert_eq!(values.len(), self.width(), "Row is the wrong length");
+    let start = (row - self.min_row) as usize * self.width();
+    self.data.splice(start..start, values);
+    self.max_row += 1;
+  }
+
+  /// Inserts a column of values so that it becomes column `col`, moving that column and
+  /// every column to the right of it along by one. Passing one past the last column appends
+  /// a column on the right.
+  pub fn insert_col(&mut self, col: isize, values: impl IntoIterator<Item = T>) {
+    assert!((self.min_col..=self.max_col + 1).contains(&col));
+    let values = values.into_iter().collect::<Vec<_>>();
+    assert_eq!(values.len(), self.height(), "Column is the wrong length");
+    let (before, after) = (
+      (col - self.min_col) as usize,
+      (self.max_col - col + 1) as usize,
+    );
+
+    let mut old_cells = std::mem::take(&mut self.data).into_iter();
+    for value in values {
+      self.data.extend(old_cells.by_ref().take(before));
+      self.data.push(value);
+      self.data.extend(old_cells.by_ref().take(after));
+    }
+    self.max_col += 1;
+  }
+
+  /// Removes a row, moving every row below it up by one, and returns its values.
+  pub fn remove_row(&mut self, row: isize) -> Vec<T> {
+    assert!(self.height() > 1, "Can't remove the only row");
+    let start = self.coord_offset(Coord::from((row, self.min_col)));
+    let removed = self.data.drain(start..start + self.width()).collect();
+    self.max_row -= 1;
+    removed
+  }
+
+  /// Removes a column, moving every column to the right of it along by one, and returns
+  /// its values.
+  pub fn remove_col(&mut self, col: isize) -> Vec<T> {
+    assert!(self.width() > 1, "Can't remove the only column");
+    assert!((self.min_col..=self.max_col).contains(&col));
+    let index = (col - self.min_col) as usize;
+    let width = self.width();
+
+    let mut removed = Vec::with_capacity(self.height());
+    let mut kept = Vec::with_capacity(self.data.len() - self.height());
+    for (i, value) in std::mem::take(&mut self.data).into_iter().enumerate() {
+      if i % width == index {
+        removed.push(value);
+      } else {
+        kept.push(value);
+      }
+    }
+    self.data = kept;
+    self.max_col -= 1;
+    removed
+  }
+
+  /// Reallocates the grid with new bounds, which must cover its current ones.
+  fn grow_to(&mut self, min: Coord, max: Coord, fill: T)
+  where
+    T: Clone,
+  {
+    let grown = Grid::with_bounds(min, max, ());
+    let mut old_cells = std::mem::take(&mut self.data).into_iter();
+
+    // The old cells appear in the same order within the new bounds, so they can be
+    // moved across as they're reached
+    self.data = grown
+      .coords()
+      .map(|coord| {
+        if self.contains(coord) {
+          old_cells.next().unwrap()
+        } else {
+          fill.clone()
+        }
+      })
+      .collect();
+    (self.min_row, self.max_row) = (min.row, max.row);
+    (self.min_col, self.max_col) = (min.col, max.col);
+  }
+
+  /// Builds a grid from lines of text, mapping each character to a cell value.
+  pub fn parse(input: &str, f: impl Fn(char) -> T) -> Self {
+    Self::try_parse(input, |c| Some(f(c))).expect("Failed to parse grid")
+  }
+
+  /// Builds a grid from lines of text, mapping each character to a cell value, or to
+  /// `None` if the character isn't valid. Fails with the first invalid character, or if
+  /// the lines aren't all the same length.
+  pub fn try_parse(input: &str, f: impl Fn(char) -> Option<T>) -> Result<Self, ParseGridError> {
+    let mut cells = Vec::new();
+    for (row, line) in input.trim_matches(['\n', '\r']).lines().enumerate() {
+      let mut cells_row = Vec::with_capacity(line.len());
+      for (col, c) in line.chars().enumerate() {
+        let coord = Coord::from((row as isize, col as isize));
+        cells_row.push(f(c).ok_or(ParseGridError::InvalidChar(c, coord))?);
+      }
+      if cells
+        .first()
+        .is_some_and(|first: &Vec<T>| first.len() != cells_row.len())
+      {
+        return Err(ParseGridError::RaggedRow(row));
+      }
+      cells.push(cells_row);
+    }
+
+    if cells.is_empty() {
+      return Err(ParseGridError::Empty);
+    }
+    Ok(Self::new(cells))
+  }
+
+  pub fn map<U, F>(&self, f: F) -> Grid<U>
+  where
+    F: Fn(GridCell<T>) -> U,
+  {
+    let data = self.cells().map(&f).collect();
+    Grid {
+      data,
+      min_row: self.min_row,
+      max_row: self.max_row,
+      min_col: self.min_col,
+      max_col: self.max_col,
+    }
+  }
+
+  pub fn cell<C: Into<Coord>>(&self, coord: C) -> GridCell<'_, T> {
+    GridCell::new(coord.into(), self)
+  }
+
+  pub fn cell_mut<C: Into<Coord>>(&mut self, coord: C) -> GridCellMut<'_, T> {
+    GridCellMut {
+      coord: coord.into(),
+      source: self,
+    }
+  }
+
+  /// Visits every cell in row-major order with a handle that can change both the cell and
+  /// its neighbours, so values can be pushed along to cells that haven't been visited yet.
+  pub fn sweep(&mut self, mut f: impl FnMut(GridCellMut<'_, T>)) {
+    for coord in self.coords() {
+      f(self.cell_mut(coord));
+    }
+  }
+
+  pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = GridCell<'_, T>>> {
+    (self.min_row..=self.max_row).map(move |row| {
+      (self.min_col..=self.max_col).map(move |col| GridCell::new(Coord { row, col }, self))
+    })
+  }
+
+  pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = GridCell<'_, T>>> {
+    (self.min_col..=self.max_col).map(move |col| {
+      (self.min_row..=self.max_row).map(move |row| GridCell::new(Coord { row, col }, self))
+    })
+  }
+
+  /// The values in a row, from left to right.
+  pub fn row(&self, row: isize) -> &[T] {
+    let start = self.coord_offset(Coord::from((row, self.min_col)));
+    &self.data[start..start + self.width()]
+  }
+
+  pub fn row_mut(&mut self, row: isize) -> &mut [T] {
+    let start = self.coord_offset(Coord::from((row, self.min_col)));
+    let width = self.width();
+    &mut self.data[start..start + width]
+  }
+
+  /// The values in a column, from top to bottom.
+  pub fn col(&self, col: isize) -> impl Iterator<Item = &T> {
+    let start = self.coord_offset(Coord::from((self.min_row, col)));
+    self.data[start..].iter().step_by(self.width())
+  }
+
+  pub fn col_mut(&mut self, col: isize) -> impl Iterator<Item = &mut T> {
+    let start = self.coord_offset(Coord::from((self.min_row, col)));
+    let width = self.width();
+    self.data[start..].iter_mut().step_by(width)
+  }
+
+  pub fn coords(&self) -> impl Iterator<Item = Coord> + 'static {
+    let rows = self.min_row..=self.max_row;
+    let cols = self.min_col..=self.max_col;
+    rows.flat_map(move |row| cols.clone().map(move |col| Coord { row, col }))
+  }
+
+  pub fn cells(&self) -> impl Iterator<Item = GridCell<'_, T>> {
+    self.coords().map(|coord| GridCell::new(coord, self))
+  }
+
+  pub fn cells_mut(&mut self) -> impl Iterator<Item = (Coord, &mut T)> {
+    let rows = self.min_row..=self.max_row;
+    let cols = self.min_col..=self.max_col;
+    let coords = rows.flat_map(move |row| cols.clone().map(move |col| Coord { row, col }));
+    coords.zip(self.data.iter_mut())
+  }
+
+  /// The coords of every cell whose value matches the predicate, in row-major order.
+  pub fn positions(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = Coord> {
+    self.coords().filter(move |&coord| predicate(&self[coord]))
+  }
+
+  /// The coord of the first cell (in row-major order) holding the given value.
+  pub fn find(&self, value: T) -> Option<Coord>
+  where
+    T: PartialEq,
+  {
+    self.positions(|v| *v == value).next()
+  }
+
+  pub fn contains(&self, Coord { row, col }: Coord) -> bool {
+    row >= self.min_row && row <= self.max_row && col >= self.min_col && col <= self.max_col
+  }
+
+  /// The top-left and bottom-right corners of the grid.
+  pub fn bounds(&self) -> (Coord, Coord) {
+    (
+      Coord::from((self.min_row, self.min_col)),
+      Coord::from((self.max_row, self.max_col)),
+    )
+  }
+
+  pub fn height(&self) -> usize {
+    (self.max_row - self.min_row + 1) as usize
+  }
+
+  pub fn width(&self) -> usize {
+    (self.max_col - self.min_col + 1) as usize
+  }
+
+  fn coord_offset(&self, Coord { row, col }: Coord) -> usize {
+    assert!(row >= self.min_row && row <= self.max_row);
+    assert!(col >= self.min_col && col <= self.max_col);
+    (row - self.min_row) as usize * (self.max_col - self.min_col + 1) as usize
+      + (col - self.min_col) as usize
+  }
+}
+
+/// Geometric transforms. Each returns a new grid with the same top-left corner as this one.
+impl<T: Clone> Grid<T> {
+  pub fn transpose(&self) -> Grid<T> {
+    self.remap(self.width(), self.height(), |row, col| (col, row))
+  }
+
+  /// Rotates the grid 90° clockwise.
+  pub fn rotate_cw(&self) -> Grid<T> {
+    let height = self.height();
+    self.remap(self.width(), height, |row, col| (height - 1 - col, row))
+  }
+
+  /// Rotates the grid 90° counterclockwise.
+  pub fn rotate_ccw(&self) -> Grid<T> {
+    let width = self.width();
+    self.remap(width, self.height(), |row, col| (col, width - 1 - row))
+  }
+
+  /// Mirrors the grid left to right.
+  pub fn flip_h(&self) -> Grid<T> {
+    let width = self.width();
+    self.remap(self.height(), width, |row, col| (row, width - 1 - col))
+  }
+
+  /// Mirrors the grid top to bottom.
+  pub fn flip_v(&self) -> Grid<T> {
+    let height = self.height();
+    self.remap(height, self.width(), |row, col| (height - 1 - row, col))
+  }
+
+  /// Every distinct way the grid can be rotated and flipped (up to 8, but fewer if the grid
+  /// is symmetric), starting with the grid as it is.
+  pub fn orientations(&self) -> impl Iterator<Item = Grid<T>> + use<T>
+  where
+    T: PartialEq,
+  {
+    let mut orientations: Vec<Grid<T>> = Vec::with_capacity(8);
+    for start in [self.clone(), self.flip_h()] {
+      let mut current = start;
+      for _ in 0..4 {
+        let next = current.rotate_cw();
+        if !orientations.contains(&current) {
+          orientations.push(current);
+        }
+        current = next;
+      }
+    }
+    orientations.into_iter()
+  }
+
+  /// Builds a grid of the given size, where the cell at each (zero-based) position is
+  /// copied from the position in this grid returned by `source`.
+  fn remap(
+    &self,
+    height: usize,
+    width: usize,
+    source: impl Fn(usize, usize) -> (usize, usize),
+  ) -> Grid<T> {
+    let mut data = Vec::with_capacity(height * width);
+    for row in 0..height {
+      for col in 0..width {
+        let (from_row, from_col) = source(row, col);
+        data.push(self.data[from_row * self.width() + from_col].clone());
+      }
+    }
+    Grid {
+      data,
+      min_row: self.min_row,
+      max_row: self.min_row + height as isize - 1,
+      min_col: self.min_col,
+      max_col: self.min_col + width as isize - 1,
+    }
+  }
+}
+
+impl<T> Grid<T> {
+  /// Renders the grid as text, one line per row, mapping each cell to a character. With
+  /// the inverse mapping, the result can be read back in using `Grid::parse`.
+  pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+    render(self, f)
+  }
+
+  /// Renders the grid like `render`, with row numbers down the left and column numbers
+  /// (written vertically) across the top, to help find cells in large grids.
+  pub fn render_with_rulers(&self, f: impl Fn(&T) -> char) -> String {
+    render_with_rulers(self, f)
+  }
+}
+
+/// Displays each cell using its own `Display` impl, which is expected to produce exactly
+/// one character per cell (e.g. a `Grid<char>`, or a grid of single-digit numbers).
+impl<T: Display> Display for Grid<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    display(self, f)
+  }
+}
+
+impl<T> GridSource<T> for Grid<T> {
+  fn contains(&self, coord: Coord) -> bool {
+    self.contains(coord)
+  }
+
+  fn value_at(&self, coord: Coord) -> &T {
+    &self[coord]
+  }
+
+  fn row_range(&self) -> RangeInclusive<isize> {
+    self.min_row..=self.max_row
+  }
+
+  fn col_range(&self) -> RangeInclusive<isize> {
+    self.min_col..=self.max_col
+  }
+}
+
+fn render<T>(source: &impl GridSource<T>, f: impl Fn(&T) -> char) -> String {
+  let mut text = String::new();
+  for row in source.row_range() {
+    text.extend(
+      source
+        .col_range()
+        .map(|col| f(source.value_at(Coord { row, col }))),
+    );
+    text.push('\n');
+  }
+  text
+}
+
+fn render_with_rulers<T>(source: &impl GridSource<T>, f: impl Fn(&T) -> char) -> String {
+  let label_width =
+    |range: RangeInclusive<isize>| range.map(|i| i.to_string().len()).max().unwrap_or(0);
+  let row_width = label_width(source.row_range());
+  let col_height = label_width(source.col_range());
+  let col_labels = source
+    .col_range()
+    .map(|col| format!("{col:>col_height$}").into_bytes())
+    .collect::<Vec<_>>();
+
+  let mut text = String::new();
+  for i in 0..col_height {
+    text.push_str(&" ".repeat(row_width + 1));
+    text.extend(col_labels.iter().map(|label| label[i] as char));
+    text.push('\n');
+  }
+  for row in source.row_range() {
+    text.push_str(&format!("{row:>row_width$} "));
+    text.extend(
+      source
+        .col_range()
+        .map(|col| f(source.value_at(Coord { row, col }))),
+    );
+    text.push('\n');
+  }
+  text
+}
+
+fn display<T: Display>(source: &impl GridSource<T>, f: &mut Formatter<'_>) -> FmtResult {
+  for row in source.row_range() {
+    for col in source.col_range() {
+      write!(f, "{}", source.value_at(Coord { row, col }))?;
+    }
+    writeln!(f)?;
+  }
+  Ok(())
+}
+
+impl<T: Debug> Debug for Grid<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let rows = self
+      .rows()
+      .map(|row| row.map(|cell| cell.value()).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    f.debug_struct("Grid")
+      .field("rows", &(self.min_row..=self.max_row))
+      .field("cols", &(self.min_col..=self.max_col))
+      .field("cells", &rows)
+      .finish()
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+  Empty,
+  InvalidChar(char, Coord),
+  RaggedRow(usize),
+}
+
+impl Display for ParseGridError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      ParseGridError::Empty => write!(f, "Grid has no rows"),
+      ParseGridError::InvalidChar(c, Coord { row, col }) => {
+        write!(f, "Invalid character {c:?} at row {row}, column {col}")
+      }
+      ParseGridError::RaggedRow(row) => {
+        write!(f, "Row {row} has a different length from the rows above it")
+      }
+    }
+  }
+}
+
+impl Error for ParseGridError {}
+
+impl<T, C: Into<Coord>> Index<C> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, coord: C) -> &Self::Output {
+    let coord = coord.into();
+    &self.data[self.coord_offset(coord)]
+  }
+}
+
+impl<T, C: Into<Coord>> IndexMut<C> for Grid<T> {
+  fn index_mut(&mut self, coord: C) -> &mut Self::Output {
+    let coord = coord.into();
+    let offset = self.coord_offset(coord);
+    &mut self.data[offset]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use indoc::indoc;
+
+  #[test]
+  fn test_coord_arithmetic() {
+    let a = Coord { row: 2, col: -3 };
+    let b = Coord { row: -1, col: 4 };
+    assert_eq!(a + b, Coord { row: 1, col: 1 });
+    assert_eq!(a - b, Coord { row: 3, col: -7 });
+    assert_eq!(-a, Coord { row: -2, col: 3 });
+    assert_eq!(b * 3, Coord { row: -3, col: 12 });
+    assert_eq!((a - b).signum(), Coord { row: 1, col: -1 });
+    assert_eq!(a.manhattan_distance(b), 10);
+    assert_eq!(a.chebyshev_distance(b), 7);
+    assert_eq!(a.translate(Direction::SW), a + Direction::SW.into());
+  }
+
+  #[test]
+  fn test_neighbours() {
+    let grid = Grid::new(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    let values = |cells: Vec<GridCell<i32>>| cells.iter().map(|c| **c).collect::<Vec<_>>();
+
+    assert_eq!(values(grid.cell((0, 0)).neighbours().collect()), [2, 5, 4]);
+    assert_eq!(
+      values(grid.cell((1, 1)).cardinal_neighbours().collect()),
+      [2, 6, 4]
+    );
+    assert_eq!(
+      values(grid.cell((1, 1)).neighbours_in([(-1, 1), (0, 5)]).collect()),
+      [3]
+    );
+    assert_eq!(grid.cell((0, 1)).count_neighbours(|&v| v % 2 == 0), 2);
+  }
+
+  #[test]
+  fn test_parse() {
+    let grid = Grid::parse("..#\n.S.\n", |c| c);
+    assert_eq!(grid.find('S'), Some(Coord { row: 1, col: 1 }));
+    assert_eq!(grid.find('X'), None);
+    assert_eq!(grid.positions(|&c| c != '.').count(), 2);
+
+    let wall = |c| match c {
+      '#' => Some(true),
+      '.' => Some(false),
+      _ => None,
+    };
+    assert!(Grid::try_parse("..#\n...", wall).is_ok());
+    assert_eq!(
+      Grid::try_parse("..#\n.S.", wall).err(),
+      Some(ParseGridError::InvalidChar('S', Coord { row: 1, col: 1 }))
+    );
+    assert_eq!(
+      Grid::try_parse("..#\n..", wall).err(),
+      Some(ParseGridError::RaggedRow(1))
+    );
+  }
+
+  #[test]
+  fn test_render() {
+    let text = "#..\n.#.\n";
+    let grid = Grid::parse(text, |c| c == '#');
+    let render = |&wall: &bool| if wall { '#' } else { '.' };
+    assert_eq!(grid.render(render), text);
+    assert_eq!(Grid::parse(&grid.render(render), |c| c == '#'), grid);
+    assert_eq!(Grid::parse(text, |c| c).to_string(), text);
+
+    let grid = Grid::new(vec![vec!['.'; 12]; 2]);
+    let expected = "            11\n  012345678901\n0 ............\n1 ............\n";
+    assert_eq!(grid.render_with_rulers(|&c| c), expected);
+  }
+
+  #[test]
+  fn test_mutable_access() {
+    let mut grid = Grid::new(vec![vec![0; 3]; 3]);
+    for (coord, value) in grid.cells_mut() {
+      *value = coord.row * 10 + coord.col;
+    }
+    assert_eq!(grid[(2, 1)], 21);
+
+    let mut cell = grid.cell_mut((1, 1));
+    *cell += 100;
+    *cell.neighbour_mut(Direction::N).unwrap() = -1;
+    assert_eq!(*cell.neighbour(Direction::N).unwrap(), -1);
+    assert!(
+      cell
+        .into_neighbour(Direction::E)
+        .unwrap()
+        .into_neighbour(Direction::E)
+        .is_none()
+    );
+
+    // Pascal's triangle, built by pushing each value down to its neighbours
+    let mut grid = Grid::new(vec![vec![0u64; 5]; 5]);
+    grid[(0, 0)] = 1;
+    grid.sweep(|mut cell| {
+      let value = *cell;
+      for dir in [Direction::S, Direction::SE] {
+        if let Some(below) = cell.neighbour_mut(dir) {
+          *below += value;
+        }
+      }
+    });
+    assert_eq!(
+      grid.rows().last().unwrap().map(|c| *c).collect::<Vec<_>>(),
+      [1, 4, 6, 4, 1]
+    );
+  }
+
+  #[test]
+  fn test_growing() {
+    let mut grid = Grid::with_bounds((-1, -1), (0, 0), '.');
+    grid[(-1, -1)] = 'a';
+    grid.expand_to_include((1, -2), '_');
+    assert_eq!(grid.bounds(), ((-1, -2).into(), (1, 0).into()));
+    assert_eq!(grid.to_string(), "_a.\n_..\n___\n");
+
+    grid.pad(1, ' ');
+    assert_eq!((grid.height(), grid.width()), (5, 5));
+    assert_eq!(grid[(-1, -1)], 'a');
+    let expected = "   ---  \n   32101\n-2      \n-1  _a. \n 0  _.. \n 1  ___ \n 2      \n";
+    assert_eq!(grid.render_with_rulers(|&c| c), expected);
+  }
+
+  #[test]
+  fn test_rows_and_cols() {
+    let mut grid = Grid::parse("abc\ndef", |c| c);
+    assert_eq!(grid.row(1), ['d', 'e', 'f']);
+    assert_eq!(grid.col(2).collect::<String>(), "cf");
+    let cols = grid
+      .cols()
+      .map(|col| col.map(|cell| *cell).collect::<String>())
+      .collect::<Vec<_>>();
+    assert_eq!(cols, ["ad", "be", "cf"]);
+
+    grid.row_mut(0).reverse();
+    grid.col_mut(1).for_each(|c| *c = c.to_ascii_uppercase());
+    assert_eq!(
+      grid.to_string(),
+      indoc! {"
+        cBa
+        dEf
+      "}
+    );
+
+    grid.insert_row(1, ['.'; 3]);
+    grid.insert_col(3, ['|'; 3]);
+    grid.insert_col(0, ['>'; 3]);
+    assert_eq!(
+      grid.to_string(),
+      indoc! {"
+        >cBa|
+        >...|
+        >dEf|
+      "}
+    );
+    assert_eq!(grid.remove_col(2), ['B', '.', 'E']);
+    assert_eq!(grid.remove_row(0), ['>', 'c', 'a', '|']);
+    assert_eq!(
+      grid.to_string(),
+      indoc! {"
+        >..|
+        >df|
+      "}
+    );
+    assert_eq!(grid.bounds(), ((0, 0).into(), (1, 3).into()));
+  }
+
+  #[test]
+  fn test_transforms() {
+    let grid = Grid::parse("ab\ncd\nef", |c| c);
+    assert_eq!(grid.transpose().to_string(), "ace\nbdf\n");
+    assert_eq!(grid.rotate_cw().to_string(), "eca\nfdb\n");
+    assert_eq!(grid.rotate_ccw().to_string(), "bdf\nace\n");
+    assert_eq!(grid.flip_h().to_string(), "ba\ndc\nfe\n");
+    assert_eq!(grid.flip_v().to_string(), "ef\ncd\nab\n");
+    assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    assert_eq!(grid.orientations().count(), 8);
+
+    let symmetric = Grid::parse("#.#\n###", |c| c == '#');
+    assert_eq!(symmetric.orientations().count(), 4);
+    let square = Grid::parse("##\n##", |c| c == '#');
+    assert_eq!(square.orientations().count(), 1);
+  }
+
+  #[test]
+  fn test_direction_turns() {
+    use Direction::*;
+    assert_eq!(N.turn_left(), W);
+    assert_eq!(W.turn_right(), N);
+    assert_eq!(NW.turn_right_45(), N);
+    assert_eq!(N.turn_left_45(), NW);
+    assert_eq!(SE.opposite(), NW);
+    assert!(Direction::diagonals().all(|d| d.is_diagonal()));
+    assert!(Direction::cardinals().all(|d| !d.is_diagonal()));
+  }
+
+  #[test]
+  fn test_direction_parsing() {
+    for d in Direction::cardinals() {
+      assert_eq!(Direction::try_from(d.arrow().unwrap()), Ok(d));
+      assert_eq!(Direction::try_from(d.udlr().unwrap()), Ok(d));
+    }
+    for d in Direction::all() {
+      assert_eq!(d.to_string().parse(), Ok(d));
+    }
+    assert!("X".parse::<Direction>().is_err());
+    assert!("NN".parse::<Direction>().is_err());
+  }
+}
@@ -0,0 +1,169 @@
+use super::{Coord, Direction, Grid, GridCell, GridSource};
+
+impl Coord {
+  /// The coords on a straight line from this coord to another, inclusive of both ends,
+  /// using Bresenham's algorithm. Lines that aren't horizontal, vertical or diagonal take
+  /// the closest path, one cell per step along their longer axis.
+  pub fn line_to(&self, end: Coord) -> impl Iterator<Item = Coord> + use<> {
+    let distance = Coord {
+      row: -end.row.abs_diff(self.row).cast_signed(),
+      col: end.col.abs_diff(self.col).cast_signed(),
+    };
+    let step = (end - *self).signum();
+    let mut current = Some(*self);
+    let mut error = distance.col + distance.row;
+
+    std::iter::from_fn(move || {
+      let coord = current?;
+      current = (coord != end).then(|| {
+        let mut next = coord;
+        let doubled = 2 * error;
+        if doubled >= distance.row {
+          error += distance.row;
+          next.col += step.col;
+        }
+        if doubled <= distance.col {
+          error += distance.col;
+          next.row += step.row;
+        }
+        next
+      });
+      Some(coord)
+    })
+  }
+}
+
+impl<'a, T, G: GridSource<T>> GridCell<'a, T, G> {
+  /// The cells passed through by travelling in a direction, for as long as their values
+  /// match the predicate.
+  pub fn travel_while<P: Fn(&T) -> bool>(
+    &self,
+    direction: Direction,
+    predicate: P,
+  ) -> impl Iterator<Item = GridCell<'a, T, G>> {
+    self
+      .travel(direction)
+      .take_while(move |cell| predicate(cell.value()))
+  }
+
+  /// The cells passed through by travelling in a direction, up to and including the first
+  /// one whose value matches the predicate (such as a wall that blocks the way).
+  pub fn travel_until<P: Fn(&T) -> bool>(
+    &self,
+    direction: Direction,
+    predicate: P,
+  ) -> impl Iterator<Item = GridCell<'a, T, G>> {
+    let mut stopped = false;
+    self.travel(direction).take_while(move |cell| {
+      let keep_going = !stopped;
+      stopped = predicate(cell.value());
+      keep_going
+    })
+  }
+
+  /// The first cell in a direction whose value matches the predicate.
+  pub fn first_hit(
+    &self,
+    direction: Direction,
+    predicate: impl Fn(&T) -> bool,
+  ) -> Option<GridCell<'a, T, G>> {
+    self.travel(direction).find(|cell| predicate(cell.value()))
+  }
+
+  /// The first cell matching the predicate in each of the eight directions, for the
+  /// directions that have one.
+  pub fn first_hits<P: Fn(&T) -> bool>(
+    &self,
+    predicate: P,
+  ) -> impl Iterator<Item = (Direction, GridCell<'a, T, G>)> + use<'a, T, G, P> {
+    let cell = *self;
+    Direction::all().filter_map(move |direction| {
+      let hit = cell.first_hit(direction, &predicate)?;
+      Some((direction, hit))
+    })
+  }
+
+  /// How many cells can be seen looking in a direction, counting the first cell that blocks
+  /// the view.
+  pub fn viewing_distance(&self, direction: Direction, blocks: impl Fn(&T) -> bool) -> usize {
+    self.travel_until(direction, blocks).count()
+  }
+
+  /// Whether there's a clear view from this cell to the edge of the grid in a direction.
+  pub fn sees_edge(&self, direction: Direction, blocks: impl Fn(&T) -> bool) -> bool {
+    self.travel(direction).all(|cell| !blocks(cell.value()))
+  }
+
+  /// Whether there's a clear straight line (see `Coord::line_to`) from this cell to another
+  /// coord, ignoring the values at either end.
+  pub fn line_of_sight(&self, target: Coord, blocks: impl Fn(&T) -> bool) -> bool {
+    let mut line = self.coord.line_to(target).skip(1).peekable();
+    while let Some(coord) = line.next() {
+      if line.peek().is_none() {
+        break;
+      }
+      if !self.source.contains(coord) || blocks(self.source.value_at(coord)) {
+        return false;
+      }
+    }
+    true
+  }
+}
+
+impl<T> Grid<T> {
+  /// Which cells can be seen from outside the grid, looking in along a row or column. `blocks`
+  /// is given the value of the cell being looked at and the value of a cell in front of it,
+  /// and says whether the one in front hides it.
+  pub fn visible_from_edges(&self, blocks: impl Fn(&T, &T) -> bool) -> Grid<bool> {
+    self.map(|cell| {
+      Direction::cardinals().any(|direction| {
+        cell
+          .travel(direction)
+          .all(|other| !blocks(cell.value(), other.value()))
+      })
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use indoc::indoc;
+
+  #[test]
+  fn test_rays() {
+    let grid = Grid::parse(
+      indoc! {"
+        30373
+        25512
+        65332
+        33549
+        35390"},
+      |c| c.to_digit(10).unwrap(),
+    );
+    let tree = grid.cell((3, 2));
+    let blocks = |&other: &u32| other >= *tree;
+    let distances = Direction::cardinals()
+      .map(|d| tree.viewing_distance(d, blocks))
+      .collect::<Vec<_>>();
+    assert_eq!(distances, [2, 2, 1, 2]);
+    assert_eq!(tree.travel_while(Direction::N, |&h| h < 5).count(), 1);
+    assert_eq!(*tree.first_hit(Direction::N, |&h| h >= 5).unwrap(), 5);
+    assert!(!tree.sees_edge(Direction::N, blocks));
+    assert!(tree.sees_edge(Direction::W, blocks));
+    assert_eq!(tree.first_hits(|&h| h == 9).count(), 2);
+
+    let visible = grid.visible_from_edges(|tree, other| other >= tree);
+    assert_eq!(visible.cells().filter(|cell| **cell).count(), 21);
+
+    let line = Coord::ORIGIN.line_to((2, 5).into()).collect::<Vec<_>>();
+    assert_eq!(
+      line,
+      [(0, 0), (0, 1), (1, 2), (1, 3), (2, 4), (2, 5)].map(Coord::from)
+    );
+    let back = Coord::from((2, 5)).line_to(Coord::ORIGIN).count();
+    assert_eq!(back, 6);
+    assert!(grid.cell((0, 0)).line_of_sight((2, 2).into(), |&h| h > 5));
+    assert!(!grid.cell((0, 0)).line_of_sight((4, 4).into(), |&h| h >= 5));
+  }
+}
@@ -0,0 +1,217 @@
+use super::{Coord, Direction, Grid};
+
+/// Which neighbours count as connected when grouping cells into regions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+  /// Only the four cardinal neighbours.
+  Four,
+  /// All eight neighbours, including diagonals.
+  Eight,
+}
+
+impl Connectivity {
+  fn directions(self) -> &'static [Direction] {
+    use Direction::*;
+    match self {
+      Connectivity::Four => &[N, E, S, W],
+      Connectivity::Eight => &[N, NE, E, SE, S, SW, W, NW],
+    }
+  }
+}
+
+/// A group of connected cells, along with some measurements of its shape.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+  pub label: usize,
+  /// One of the region's cells (the first one found in row-major order).
+  pub seed: Coord,
+  pub area: usize,
+  /// The number of cell edges between the region and anything outside it.
+  pub perimeter: usize,
+  /// The number of straight sides of the region's outline (which is the same as its number
+  /// of corners), counting the outlines of any holes.
+  pub sides: usize,
+  pub min: Coord,
+  pub max: Coord,
+  /// Whether any of the region's cells are on the edge of the grid.
+  pub touches_border: bool,
+}
+
+impl Region {
+  /// Whether the region is entirely surrounded by other regions.
+  pub fn is_enclosed(&self) -> bool {
+    !self.touches_border
+  }
+
+  /// The top-left and bottom-right corners of the smallest rectangle containing the region.
+  pub fn bounds(&self) -> (Coord, Coord) {
+    (self.min, self.max)
+  }
+}
+
+/// A grid divided up into regions, with each cell labelled by the index of its region.
+pub struct Regions {
+  labels: Grid<usize>,
+  regions: Vec<Region>,
+}
+
+impl Regions {
+  /// The label of every cell, which is also the index of its region.
+  pub fn labels(&self) -> &Grid<usize> {
+    &self.labels
+  }
+
+  pub fn len(&self) -> usize {
+    self.regions.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.regions.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Region> {
+    self.regions.iter()
+  }
+
+  pub fn get(&self, label: usize) -> Option<&Region> {
+    self.regions.get(label)
+  }
+
+  /// The region containing a cell.
+  pub fn region_at(&self, coord: impl Into<Coord>) -> &Region {
+    &self.regions[self.labels[coord.into()]]
+  }
+
+  /// A grid marking which cells are in the given region.
+  pub fn mask(&self, label: usize) -> Grid<bool> {
+    self.labels.map(|cell| *cell == label)
+  }
+}
+
+impl<T> Grid<T> {
+  /// Groups the grid's cells into connected regions, where neighbouring cells are in the
+  /// same region if `same_region` is true for their values.
+  pub fn regions(
+    &self,
+    connectivity: Connectivity,
+    same_region: impl Fn(&T, &T) -> bool,
+  ) -> Regions {
+    const UNLABELLED: usize = usize::MAX;
+    let mut labels = self.map(|_| UNLABELLED);
+    let mut regions = Vec::new();
+
+    for seed in self.coords() {
+      if labels[seed] != UNLABELLED {
+        continue;
+      }
+
+      let label = regions.len();
+      let mut region = Region {
+        label,
+        seed,
+        area: 0,
+        perimeter: 0,
+        sides: 0,
+        min: seed,
+        max: seed,
+        touches_border: false,
+      };
+      labels[seed] = label;
+      let mut pending = vec![seed];
+      while let Some(coord) = pending.pop() {
+        region.area += 1;
+        region.min = Coord::from((region.min.row.min(coord.row), region.min.col.min(coord.col)));
+        region.max = Coord::from((region.max.row.max(coord.row), region.max.col.max(coord.col)));
+
+        let cell = self.cell(coord);
+        for neighbour in cell.neighbours_in(connectivity.directions().iter().copied()) {
+          if labels[neighbour.coord] == UNLABELLED && same_region(&cell, &neighbour) {
+            labels[neighbour.coord] = label;
+            pending.push(neighbour.coord);
+          }
+        }
+      }
+      regions.push(region);
+    }
+
+    // Now every cell is labelled, measure each region's outline
+    let (min, max) = self.bounds();
+    for coord in self.coords() {
+      let label = labels[coord];
+      let inside = |direction: Direction| {
+        let coord = coord + direction.into();
+        labels.contains(coord) && labels[coord] == label
+      };
+
+      let region = &mut regions[label];
+      region.perimeter += Direction::cardinals().filter(|&d| !inside(d)).count();
+      for direction in Direction::cardinals() {
+        let (ahead, right) = (inside(direction), inside(direction.turn_right()));
+        let convex = !ahead && !right;
+        let concave = ahead && right && !inside(direction.turn_right_45());
+        region.sides += (convex || concave) as usize;
+      }
+      region.touches_border |= coord.row == min.row
+        || coord.row == max.row
+        || coord.col == min.col
+        || coord.col == max.col;
+    }
+
+    Regions { labels, regions }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use indoc::indoc;
+
+  #[test]
+  fn test_regions() {
+    let grid = Grid::parse(
+      indoc! {"
+        AAAA
+        BBCD
+        BBCC
+        EEEC"},
+      |c| c,
+    );
+    let regions = grid.regions(Connectivity::Four, |a, b| a == b);
+    let metrics = regions
+      .iter()
+      .map(|r| (grid[r.seed], r.area, r.perimeter, r.sides))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      metrics,
+      [
+        ('A', 4, 10, 4),
+        ('B', 4, 8, 4),
+        ('C', 4, 10, 8),
+        ('D', 1, 4, 4),
+        ('E', 3, 8, 4)
+      ]
+    );
+    assert_eq!(
+      regions.region_at((2, 3)).bounds(),
+      ((1, 2).into(), (3, 3).into())
+    );
+
+    let grid = Grid::parse(
+      indoc! {"
+        OOOOO
+        OXOXO
+        OOOOO
+        OXOXO
+        OOOOO"},
+      |c| c == 'X',
+    );
+    let regions = grid.regions(Connectivity::Four, |a, b| a == b);
+    assert_eq!(regions.len(), 5);
+    assert_eq!(regions.region_at((0, 0)).sides, 20);
+    assert_eq!(regions.iter().filter(|r| r.is_enclosed()).count(), 4);
+
+    let grid = Grid::parse("X.\n.X", |c| c);
+    assert_eq!(grid.regions(Connectivity::Four, |a, b| a == b).len(), 4);
+    assert_eq!(grid.regions(Connectivity::Eight, |a, b| a == b).len(), 2);
+  }
+}
@@ -0,0 +1,333 @@
+use std::{
+  cmp::Reverse,
+  collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use super::{Coord, Direction, Grid, GridCell};
+
+/// A single move considered by a search, from a cell to one of its cardinal neighbours.
+pub struct Step<'a, T> {
+  pub from: GridCell<'a, T>,
+  pub to: GridCell<'a, T>,
+  pub direction: Direction,
+  /// The direction of the move that reached `from`, or `None` if `from` is the start.
+  pub facing: Option<Direction>,
+}
+
+impl<T> Step<'_, T> {
+  /// Whether this move changes direction from the one before it.
+  pub fn turns(&self) -> bool {
+    self.facing.is_some_and(|facing| facing != self.direction)
+  }
+}
+
+/// A search state: where we are, and (for searches whose costs depend on it) which way we
+/// were moving when we got there.
+type State = (Coord, Option<Direction>);
+
+/// The result of searching a grid from a start cell: how far away each reachable cell is,
+/// and every way of reaching it at that distance.
+pub struct Paths {
+  start: Coord,
+  bounds: (Coord, Coord),
+  best: HashMap<State, u64>,
+  previous: HashMap<State, Vec<State>>,
+}
+
+impl Paths {
+  pub fn start(&self) -> Coord {
+    self.start
+  }
+
+  /// The length (or total cost) of the shortest path to a coord, if it was reached.
+  pub fn distance(&self, coord: impl Into<Coord>) -> Option<u64> {
+    let coord = coord.into();
+    self.end_states(coord).first().map(|state| self.best[state])
+  }
+
+  /// The distance to every cell, or `None` for cells that weren't reached.
+  pub fn distances(&self) -> Grid<Option<u64>> {
+    let (min, max) = self.bounds;
+    let mut distances = Grid::with_bounds(min, max, None);
+    for (&(coord, _), &distance) in &self.best {
+      let best = &mut distances[coord];
+      *best = Some(best.map_or(distance, |best: u64| best.min(distance)));
+    }
+    distances
+  }
+
+  /// One of the shortest paths to a coord, from the start to the coord inclusive.
+  pub fn path_to(&self, coord: impl Into<Coord>) -> Option<Vec<Coord>> {
+    let mut state = *self.end_states(coord.into()).first()?;
+    let mut path = vec![state.0];
+    while let Some(previous) = self.previous.get(&state) {
+      state = previous[0];
+      path.push(state.0);
+    }
+    path.reverse();
+    Some(path)
+  }
+
+  /// Every cell that's on at least one of the shortest paths to a coord.
+  pub fn on_shortest_paths(&self, coord: impl Into<Coord>) -> Grid<bool> {
+    let (min, max) = self.bounds;
+    let mut on_path = Grid::with_bounds(min, max, false);
+    let mut pending = self.end_states(coord.into());
+    let mut seen = pending.iter().copied().collect::<HashSet<_>>();
+    while let Some(state) = pending.pop() {
+      on_path[state.0] = true;
+      for &previous in self.previous.get(&state).into_iter().flatten() {
+        if seen.insert(previous) {
+          pending.push(previous);
+        }
+      }
+    }
+    on_path
+  }
+
+  /// The states at a coord that were reached at the shortest distance.
+  fn end_states(&self, coord: Coord) -> Vec<State> {
+    let ends = Direction::cardinals()
+      .map(Some)
+      .chain([None])
+      .map(|facing| (coord, facing))
+      .filter(|state| self.best.contains_key(state))
+      .collect::<Vec<_>>();
+    let Some(shortest) = ends.iter().map(|state| self.best[state]).min() else {
+      return Vec::new();
+    };
+    ends
+      .into_iter()
+      .filter(|state| self.best[state] == shortest)
+      .collect()
+  }
+}
+
+impl<T> Grid<T> {
+  /// Finds the shortest paths from a start cell to every cell reachable through cells
+  /// matching `passable`, moving one cardinal step at a time.
+  pub fn bfs(&self, start: impl Into<Coord>, passable: impl Fn(&T) -> bool) -> Paths {
+    let start = start.into();
+    let mut paths = self.paths_from(start);
+    let mut queue = VecDeque::from([start]);
+    while let Some(coord) = queue.pop_front() {
+      let distance = paths.best[&(coord, None)];
+      for next in self.cell(coord).cardinal_neighbours() {
+        if !passable(&next) {
+          continue;
+        }
+        let next = (next.coord, None);
+        match paths.best.get(&next) {
+          None => {
+            paths.best.insert(next, distance + 1);
+            queue.push_back(next.0);
+          }
+          Some(&best) if best == distance + 1 => {}
+          Some(_) => continue,
+        }
+        paths.previous.entry(next).or_default().push((coord, None));
+      }
+    }
+    paths
+  }
+
+  /// Finds the cheapest paths from a start cell to every reachable cell. `cost` gives the
+  /// cost of each move, or `None` if the move isn't allowed; since it sees the direction
+  /// of the previous move, it can charge for turning.
+  pub fn dijkstra(
+    &self,
+    start: impl Into<Coord>,
+    cost: impl Fn(&Step<'_, T>) -> Option<u64>,
+  ) -> Paths {
+    self.search(start.into(), None, cost, |_| 0)
+  }
+
+  /// Finds the cheapest paths from a start cell to a goal, like `dijkstra`, but using a
+  /// heuristic to search towards the goal first. The heuristic must never overestimate
+  /// the remaining cost (the Manhattan distance to the goal is a good choice when every
+  /// move costs at least 1). Cells further away than the goal may not have been reached.
+  pub fn astar(
+    &self,
+    start: impl Into<Coord>,
+    goal: impl Into<Coord>,
+    cost: impl Fn(&Step<'_, T>) -> Option<u64>,
+    heuristic: impl Fn(Coord) -> u64,
+  ) -> Paths {
+    self.search(start.into(), Some(goal.into()), cost, heuristic)
+  }
+
+  fn paths_from(&self, start: Coord) -> Paths {
+    assert!(self.contains(start));
+    Paths {
+      start,
+      bounds: self.bounds(),
+      best: HashMap::from([((start, None), 0)]),
+      previous: HashMap::new(),
+    }
+  }
+
+  fn search(
+    &self,
+    start: Coord,
+    goal: Option<Coord>,
+    cost: impl Fn(&Step<'_, T>) -> Option<u64>,
+    heuristic: impl Fn(Coord) -> u64,
+  ) -> Paths {
+    let mut paths = self.paths_from(start);
+    let mut goal_distance = None;
+    let mut queue = BinaryHeap::from([Reverse((heuristic(start), 0, (start, None)))]);
+
+    while let Some(Reverse((estimate, distance, state))) = queue.pop() {
+      if distance > paths.best[&state] {
+        continue;
+      }
+      // Keep going until every equally short path to the goal has been found
+      if goal_distance.is_some_and(|goal_distance| estimate > goal_distance) {
+        break;
+      }
+      let (coord, facing) = state;
+      if goal == Some(coord) {
+        goal_distance.get_or_insert(distance);
+        continue;
+      }
+
+      let from = self.cell(coord);
+      for direction in Direction::cardinals() {
+        let Some(to) = from.neighbour(direction) else {
+          continue;
+        };
+        let step = Step {
+          from,
+          to,
+          direction,
+          facing,
+        };
+        let Some(step_cost) = cost(&step) else {
+          continue;
+        };
+
+        let next = (to.coord, Some(direction));
+        let next_distance = distance + step_cost;
+        match paths.best.get(&next) {
+          Some(&best) if best < next_distance => continue,
+          Some(&best) if best == next_distance => {}
+          _ => {
+            paths.best.insert(next, next_distance);
+            paths.previous.insert(next, Vec::new());
+            let estimate = next_distance + heuristic(to.coord);
+            queue.push(Reverse((estimate, next_distance, next)));
+          }
+        }
+        paths.previous.entry(next).or_default().push(state);
+      }
+    }
+
+    paths
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use indoc::indoc;
+
+  fn maze() -> Grid<bool> {
+    Grid::parse(
+      indoc! {"
+        .....
+        .###.
+        .....
+        ##.#.
+        ....."},
+      |c| c == '.',
+    )
+  }
+
+  fn open(step: &Step<'_, bool>) -> Option<u64> {
+    step.to.then_some(1)
+  }
+
+  #[test]
+  fn test_bfs() {
+    let bfs = maze().bfs((0, 0), |&open| open);
+    assert_eq!(bfs.distance((4, 0)), Some(8));
+    assert_eq!(bfs.distance((1, 1)), None);
+    assert_eq!(
+      bfs
+        .distances()
+        .render(|d| d.map_or('#', |d| char::from_digit(d as u32, 10).unwrap())),
+      indoc! {"
+        01234
+        1###5
+        23456
+        ##5#7
+        87678
+      "}
+    );
+    assert_eq!(
+      bfs
+        .on_shortest_paths((4, 4))
+        .render(|&on| if on { 'O' } else { '.' }),
+      indoc! {"
+        OOOOO
+        O...O
+        OOOOO
+        ..O.O
+        ..OOO
+      "}
+    );
+  }
+
+  #[test]
+  fn test_dijkstra() {
+    let grid = maze();
+    let dijkstra = grid.dijkstra((0, 0), open);
+    assert_eq!(
+      dijkstra.distances(),
+      grid.bfs((0, 0), |&open| open).distances()
+    );
+    assert_eq!(dijkstra.path_to((4, 4)).unwrap().len(), 9);
+  }
+
+  #[test]
+  fn test_turn_costs() {
+    // Turning costs extra, so the path with the fewest turns wins
+    let turns = maze().dijkstra((0, 0), |step| {
+      step.to.then_some(if step.turns() { 10 } else { 1 })
+    });
+    assert_eq!(turns.distance((4, 4)), Some(17));
+    assert_eq!(
+      turns
+        .path_to((4, 4))
+        .unwrap()
+        .into_iter()
+        .map(|c| (c.row, c.col))
+        .collect::<Vec<_>>(),
+      [
+        (0, 0),
+        (0, 1),
+        (0, 2),
+        (0, 3),
+        (0, 4),
+        (1, 4),
+        (2, 4),
+        (3, 4),
+        (4, 4)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_astar() {
+    let grid = maze();
+    let goal = Coord::from((4, 4));
+    let astar = grid.astar((0, 0), goal, open, |coord| {
+      coord.manhattan_distance(goal) as u64
+    });
+    assert_eq!(astar.distance(goal), Some(8));
+    assert_eq!(
+      astar.on_shortest_paths(goal),
+      grid.bfs((0, 0), |&open| open).on_shortest_paths(goal)
+    );
+  }
+}
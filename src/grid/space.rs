@@ -0,0 +1,586 @@
+use std::{
+  collections::HashMap,
+  marker::PhantomData,
+  ops::{Add, AddAssign, Deref, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+};
+
+use super::Coord;
+
+pub type Coord3 = CoordN<3>;
+pub type Grid3<T> = GridN<T, 3>;
+pub type SparseGrid3<T> = SparseGridN<T, 3>;
+pub type Cell3<'a, T> = CellN<'a, T, 3>;
+pub type SparseCellN<'a, T, const D: usize> = CellN<'a, T, D, SparseGridN<T, D>>;
+pub type SparseCell3<'a, T> = SparseCellN<'a, T, 3>;
+
+/// A position in `D`-dimensional space, or an offset between two positions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CoordN<const D: usize>(pub [isize; D]);
+
+impl<const D: usize> From<[isize; D]> for CoordN<D> {
+  fn from(axes: [isize; D]) -> Self {
+    CoordN(axes)
+  }
+}
+
+impl From<(isize, isize, isize)> for Coord3 {
+  fn from((x, y, z): (isize, isize, isize)) -> Self {
+    CoordN([x, y, z])
+  }
+}
+
+impl From<Coord> for CoordN<2> {
+  fn from(coord: Coord) -> Self {
+    CoordN([coord.row, coord.col])
+  }
+}
+
+impl From<CoordN<2>> for Coord {
+  fn from(CoordN([row, col]): CoordN<2>) -> Self {
+    Coord { row, col }
+  }
+}
+
+impl Coord3 {
+  pub fn new(x: isize, y: isize, z: isize) -> Self {
+    CoordN([x, y, z])
+  }
+
+  pub fn x(&self) -> isize {
+    self.0[0]
+  }
+
+  pub fn y(&self) -> isize {
+    self.0[1]
+  }
+
+  pub fn z(&self) -> isize {
+    self.0[2]
+  }
+}
+
+impl<const D: usize> CoordN<D> {
+  pub const ORIGIN: CoordN<D> = CoordN([0; D]);
+
+  pub fn manhattan_distance(&self, other: CoordN<D>) -> usize {
+    (0..D).map(|axis| self[axis].abs_diff(other[axis])).sum()
+  }
+
+  /// The number of king's moves (including diagonals) needed to get from one coord to the
+  /// other.
+  pub fn chebyshev_distance(&self, other: CoordN<D>) -> usize {
+    (0..D)
+      .map(|axis| self[axis].abs_diff(other[axis]))
+      .max()
+      .unwrap_or(0)
+  }
+
+  /// The square of the straight-line distance between two coords, which (unlike the
+  /// distance itself) is always a whole number.
+  pub fn distance_squared(&self, other: CoordN<D>) -> usize {
+    (0..D)
+      .map(|axis| self[axis].abs_diff(other[axis]).pow(2))
+      .sum()
+  }
+
+  /// The offsets to all `3^D - 1` surrounding coords (26 in three dimensions), including
+  /// diagonals.
+  pub fn neighbour_offsets() -> impl Iterator<Item = CoordN<D>> {
+    (0..3usize.pow(D as u32)).filter_map(|mut n| {
+      let mut offset = [0; D];
+      for axis in &mut offset {
+        *axis = (n % 3) as isize - 1;
+        n /= 3;
+      }
+      (offset != [0; D]).then_some(CoordN(offset))
+    })
+  }
+
+  /// The offsets to the `2 * D` coords one step away along a single axis (6 in three
+  /// dimensions).
+  pub fn orthogonal_offsets() -> impl Iterator<Item = CoordN<D>> {
+    (0..D).flat_map(|axis| {
+      [-1, 1].map(|step| {
+        let mut offset = [0; D];
+        offset[axis] = step;
+        CoordN(offset)
+      })
+    })
+  }
+
+  pub fn neighbours(&self) -> impl Iterator<Item = CoordN<D>> + use<D> {
+    let centre = *self;
+    Self::neighbour_offsets().map(move |offset| centre + offset)
+  }
+
+  pub fn orthogonal_neighbours(&self) -> impl Iterator<Item = CoordN<D>> + use<D> {
+    let centre = *self;
+    Self::orthogonal_offsets().map(move |offset| centre + offset)
+  }
+
+  fn zip_with(self, other: CoordN<D>, f: impl Fn(isize, isize) -> isize) -> CoordN<D> {
+    CoordN(std::array::from_fn(|axis| f(self[axis], other[axis])))
+  }
+}
+
+impl<const D: usize> Index<usize> for CoordN<D> {
+  type Output = isize;
+
+  fn index(&self, axis: usize) -> &isize {
+    &self.0[axis]
+  }
+}
+
+impl<const D: usize> IndexMut<usize> for CoordN<D> {
+  fn index_mut(&mut self, axis: usize) -> &mut isize {
+    &mut self.0[axis]
+  }
+}
+
+impl<const D: usize> Add for CoordN<D> {
+  type Output = CoordN<D>;
+
+  fn add(self, other: CoordN<D>) -> CoordN<D> {
+    self.zip_with(other, |a, b| a + b)
+  }
+}
+
+impl<const D: usize> Sub for CoordN<D> {
+  type Output = CoordN<D>;
+
+  fn sub(self, other: CoordN<D>) -> CoordN<D> {
+    self.zip_with(other, |a, b| a - b)
+  }
+}
+
+impl<const D: usize> Neg for CoordN<D> {
+  type Output = CoordN<D>;
+
+  fn neg(self) -> CoordN<D> {
+    CoordN(self.0.map(|axis| -axis))
+  }
+}
+
+impl<const D: usize> Mul<isize> for CoordN<D> {
+  type Output = CoordN<D>;
+
+  fn mul(self, scale: isize) -> CoordN<D> {
+    CoordN(self.0.map(|axis| axis * scale))
+  }
+}
+
+impl<const D: usize> AddAssign for CoordN<D> {
+  fn add_assign(&mut self, other: CoordN<D>) {
+    *self = *self + other;
+  }
+}
+
+impl<const D: usize> SubAssign for CoordN<D> {
+  fn sub_assign(&mut self, other: CoordN<D>) {
+    *self = *self - other;
+  }
+}
+
+/// Storage that `CellN`s can look up values in, which lets dense and sparse grids of any
+/// number of dimensions share the same cell-handle API (like `GridSource` in 2D).
+pub trait SpaceSource<T, const D: usize> {
+  /// Whether the coord lies within the grid's bounds.
+  fn contains(&self, coord: CoordN<D>) -> bool;
+
+  /// The value at a coord within the grid's bounds.
+  fn value_at(&self, coord: CoordN<D>) -> &T;
+
+  /// Where a step to the given coord lands, or `None` if it's off the grid.
+  fn resolve(&self, coord: CoordN<D>) -> Option<CoordN<D>> {
+    self.contains(coord).then_some(coord)
+  }
+}
+
+/// A handle on one cell of a `GridN` or `SparseGridN`, which can look around at its
+/// neighbours in the same way as a 2D `GridCell`.
+pub struct CellN<'a, T, const D: usize, G = GridN<T, D>> {
+  pub coord: CoordN<D>,
+  source: &'a G,
+  values: PhantomData<&'a T>,
+}
+
+impl<T, const D: usize, G> Copy for CellN<'_, T, D, G> {}
+impl<T, const D: usize, G> Clone for CellN<'_, T, D, G> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<'a, T, const D: usize, G: SpaceSource<T, D>> CellN<'a, T, D, G> {
+  fn new(coord: CoordN<D>, source: &'a G) -> Self {
+    CellN {
+      coord,
+      source,
+      values: PhantomData,
+    }
+  }
+
+  /// The cell at an offset from this one, if it's within the grid.
+  pub fn offset(&self, offset: impl Into<CoordN<D>>) -> Option<CellN<'a, T, D, G>> {
+    let coord = self.source.resolve(self.coord + offset.into())?;
+    Some(CellN::new(coord, self.source))
+  }
+
+  /// The surrounding cells (including diagonals) that are within the grid.
+  pub fn neighbours(&self) -> impl Iterator<Item = CellN<'a, T, D, G>> + use<'a, T, D, G> {
+    let cell = *self;
+    CoordN::neighbour_offsets().filter_map(move |offset| cell.offset(offset))
+  }
+
+  /// The cells one step away along a single axis that are within the grid.
+  pub fn orthogonal_neighbours(
+    &self,
+  ) -> impl Iterator<Item = CellN<'a, T, D, G>> + use<'a, T, D, G> {
+    let cell = *self;
+    CoordN::orthogonal_offsets().filter_map(move |offset| cell.offset(offset))
+  }
+
+  /// How many of the surrounding cells (including diagonals) have a value matching the
+  /// predicate.
+  pub fn count_neighbours(&self, predicate: impl Fn(&T) -> bool) -> usize {
+    self.neighbours().filter(|cell| predicate(cell)).count()
+  }
+
+  /// The cells passed through by repeatedly moving by `step`, stopping at the edge of the
+  /// grid (the bounding box, for a sparse grid).
+  pub fn travel(&self, step: impl Into<CoordN<D>>) -> impl Iterator<Item = CellN<'a, T, D, G>> {
+    let step = step.into();
+    let mut current = *self;
+    std::iter::from_fn(move || {
+      current = current
+        .offset(step)
+        .filter(|next| next.source.contains(next.coord))?;
+      Some(current)
+    })
+  }
+
+  pub fn value(&self) -> &'a T {
+    self.source.value_at(self.coord)
+  }
+}
+
+impl<T, const D: usize, G: SpaceSource<T, D>> Deref for CellN<'_, T, D, G> {
+  type Target = T;
+
+  fn deref(&self) -> &Self::Target {
+    self.value()
+  }
+}
+
+/// A dense grid covering a box of `D`-dimensional space, whose corners may be negative.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridN<T, const D: usize> {
+  data: Vec<T>,
+  min: CoordN<D>,
+  max: CoordN<D>,
+}
+
+impl<T, const D: usize> GridN<T, D> {
+  /// Creates a grid covering every coord from `min` to `max` (inclusive), with every cell
+  /// set to `fill`.
+  pub fn with_bounds(min: impl Into<CoordN<D>>, max: impl Into<CoordN<D>>, fill: T) -> Self
+  where
+    T: Clone,
+  {
+    let (min, max) = (min.into(), max.into());
+    assert!((0..D).all(|axis| min[axis] <= max[axis]));
+    let mut grid = Self {
+      data: Vec::new(),
+      min,
+      max,
+    };
+    grid.data = vec![fill; grid.size().iter().product()];
+    grid
+  }
+
+  /// The corners of the grid with the lowest and highest coords.
+  pub fn bounds(&self) -> (CoordN<D>, CoordN<D>) {
+    (self.min, self.max)
+  }
+
+  /// The number of cells along each axis.
+  pub fn size(&self) -> [usize; D] {
+    std::array::from_fn(|axis| (self.max[axis] - self.min[axis] + 1) as usize)
+  }
+
+  pub fn contains(&self, coord: CoordN<D>) -> bool {
+    (0..D).all(|axis| (self.min[axis]..=self.max[axis]).contains(&coord[axis]))
+  }
+
+  pub fn cell(&self, coord: impl Into<CoordN<D>>) -> CellN<'_, T, D> {
+    CellN::new(coord.into(), self)
+  }
+
+  /// Every coord in the grid, ordered by the first axis, then the second, and so on.
+  pub fn coords(&self) -> impl Iterator<Item = CoordN<D>> + use<T, D> {
+    let (min, size) = (self.min, self.size());
+    (0..size.iter().product()).map(move |mut index: usize| {
+      let mut coord = min;
+      for axis in (0..D).rev() {
+        coord[axis] += (index % size[axis]) as isize;
+        index /= size[axis];
+      }
+      coord
+    })
+  }
+
+  pub fn cells(&self) -> impl Iterator<Item = CellN<'_, T, D>> {
+    self.coords().map(|coord| self.cell(coord))
+  }
+
+  pub fn map<U>(&self, f: impl Fn(CellN<T, D>) -> U) -> GridN<U, D> {
+    GridN {
+      data: self.cells().map(f).collect(),
+      min: self.min,
+      max: self.max,
+    }
+  }
+
+  /// Grows the grid so that it includes the coord, filling any new cells with `fill`.
+  pub fn expand_to_include(&mut self, coord: impl Into<CoordN<D>>, fill: T)
+  where
+    T: Clone,
+  {
+    let coord = coord.into();
+    if self.contains(coord) {
+      return;
+    }
+
+    let min = self.min.zip_with(coord, isize::min);
+    let max = self.max.zip_with(coord, isize::max);
+    let mut grown = GridN::with_bounds(min, max, None);
+    let old_coords = self.coords();
+    for (coord, value) in old_coords.zip(std::mem::take(&mut self.data)) {
+      grown[coord] = Some(value);
+    }
+    self.data = grown
+      .data
+      .into_iter()
+      .map(|value| value.unwrap_or_else(|| fill.clone()))
+      .collect();
+    (self.min, self.max) = (min, max);
+  }
+
+  fn offset_of(&self, coord: CoordN<D>) -> usize {
+    assert!(self.contains(coord), "{coord:?} is outside the grid");
+    let size = self.size();
+    (0..D).fold(0, |offset, axis| {
+      offset * size[axis] + (coord[axis] - self.min[axis]) as usize
+    })
+  }
+}
+
+impl<T, const D: usize> SpaceSource<T, D> for GridN<T, D> {
+  fn contains(&self, coord: CoordN<D>) -> bool {
+    self.contains(coord)
+  }
+
+  fn value_at(&self, coord: CoordN<D>) -> &T {
+    &self[coord]
+  }
+}
+
+impl<T, const D: usize, C: Into<CoordN<D>>> Index<C> for GridN<T, D> {
+  type Output = T;
+
+  fn index(&self, coord: C) -> &Self::Output {
+    &self.data[self.offset_of(coord.into())]
+  }
+}
+
+impl<T, const D: usize, C: Into<CoordN<D>>> IndexMut<C> for GridN<T, D> {
+  fn index_mut(&mut self, coord: C) -> &mut Self::Output {
+    let offset = self.offset_of(coord.into());
+    &mut self.data[offset]
+  }
+}
+
+/// An unbounded `D`-dimensional grid that only stores the cells that have been set, reading
+/// every other coord as a default value. Its bounds grow and shrink to fit the cells that
+/// have been set. (`SparseGrid` is the 2D version, with the `GridCell` API.)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGridN<T, const D: usize> {
+  cells: HashMap<CoordN<D>, T>,
+  default: T,
+  bounds: Option<(CoordN<D>, CoordN<D>)>,
+}
+
+impl<T, const D: usize> SparseGridN<T, D> {
+  pub fn new(default: T) -> Self {
+    Self {
+      cells: HashMap::new(),
+      default,
+      bounds: None,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.cells.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.cells.is_empty()
+  }
+
+  /// Sets the value at a coord, growing the bounds to include it, and returns the value it
+  /// replaced (if any).
+  pub fn insert(&mut self, coord: impl Into<CoordN<D>>, value: T) -> Option<T> {
+    let coord = coord.into();
+    self.bounds = Some(match self.bounds {
+      None => (coord, coord),
+      Some((min, max)) => (
+        min.zip_with(coord, isize::min),
+        max.zip_with(coord, isize::max),
+      ),
+    });
+    self.cells.insert(coord, value)
+  }
+
+  /// Unsets a coord, returning the value it held. The bounds shrink if it was the last set
+  /// cell on one of their faces.
+  pub fn remove(&mut self, coord: impl Into<CoordN<D>>) -> Option<T> {
+    let coord = coord.into();
+    let removed = self.cells.remove(&coord);
+    let on_edge = self.bounds.is_some_and(|(min, max)| {
+      (0..D).any(|axis| coord[axis] == min[axis] || coord[axis] == max[axis])
+    });
+    if removed.is_some() && on_edge {
+      self.bounds = self.cells.keys().fold(None, |bounds, &coord| match bounds {
+        None => Some((coord, coord)),
+        Some((min, max)) => Some((
+          min.zip_with(coord, isize::min),
+          max.zip_with(coord, isize::max),
+        )),
+      });
+    }
+    removed
+  }
+
+  /// The value stored at a coord, or `None` where the grid reads as the default.
+  pub fn get(&self, coord: impl Into<CoordN<D>>) -> Option<&T> {
+    self.cells.get(&coord.into())
+  }
+
+  pub fn get_mut(&mut self, coord: impl Into<CoordN<D>>) -> Option<&mut T> {
+    self.cells.get_mut(&coord.into())
+  }
+
+  pub fn cell(&self, coord: impl Into<CoordN<D>>) -> SparseCellN<'_, T, D> {
+    CellN::new(coord.into(), self)
+  }
+
+  /// The coords of every cell that has been set, in no particular order.
+  pub fn coords(&self) -> impl Iterator<Item = CoordN<D>> + '_ {
+    self.cells.keys().copied()
+  }
+
+  /// Every cell that has been set, in no particular order.
+  pub fn cells(&self) -> impl Iterator<Item = SparseCellN<'_, T, D>> {
+    self.coords().map(|coord| self.cell(coord))
+  }
+
+  /// The corners of the smallest box containing every cell that has been set, or `None`
+  /// if the grid is empty.
+  pub fn bounds(&self) -> Option<(CoordN<D>, CoordN<D>)> {
+    self.bounds
+  }
+}
+
+impl<T, const D: usize> SpaceSource<T, D> for SparseGridN<T, D> {
+  fn contains(&self, coord: CoordN<D>) -> bool {
+    self
+      .bounds
+      .is_some_and(|(min, max)| (0..D).all(|axis| (min[axis]..=max[axis]).contains(&coord[axis])))
+  }
+
+  fn value_at(&self, coord: CoordN<D>) -> &T {
+    self.cells.get(&coord).unwrap_or(&self.default)
+  }
+
+  /// Every coord is on the grid, so cells at the edge of the bounding box still see their
+  /// neighbours outside it (which read as the default value).
+  fn resolve(&self, coord: CoordN<D>) -> Option<CoordN<D>> {
+    Some(coord)
+  }
+}
+
+impl<T: Default, const D: usize> Default for SparseGridN<T, D> {
+  fn default() -> Self {
+    Self::new(T::default())
+  }
+}
+
+impl<T: Default, const D: usize, C: Into<CoordN<D>>> FromIterator<(C, T)> for SparseGridN<T, D> {
+  fn from_iter<I: IntoIterator<Item = (C, T)>>(iter: I) -> Self {
+    let mut grid = Self::default();
+    for (coord, value) in iter {
+      grid.insert(coord, value);
+    }
+    grid
+  }
+}
+
+impl<T, const D: usize, C: Into<CoordN<D>>> Index<C> for SparseGridN<T, D> {
+  type Output = T;
+
+  fn index(&self, coord: C) -> &Self::Output {
+    self.value_at(coord.into())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_grids_in_3d() {
+    let a = Coord3::new(1, -2, 3);
+    assert_eq!(a + Coord3::new(1, 1, 1), (2, -1, 4).into());
+    assert_eq!(a.manhattan_distance(Coord3::ORIGIN), 6);
+    assert_eq!(a.distance_squared(Coord3::ORIGIN), 14);
+    assert_eq!(a.neighbours().count(), 26);
+    assert_eq!(a.orthogonal_neighbours().count(), 6);
+    assert_eq!(CoordN::<4>::ORIGIN.neighbours().count(), 80);
+
+    // Surface area of a small droplet, counting faces next to air
+    let mut grid = Grid3::with_bounds((0, 0, 0), (2, 2, 2), false);
+    for cube in [(1, 1, 1), (2, 1, 1)] {
+      grid[cube] = true;
+    }
+    let exposed_faces = |cell: Cell3<bool>| {
+      let covered = cell.orthogonal_neighbours().filter(|n| **n).count();
+      6 - covered
+    };
+    let surface: usize = grid.cells().filter(|c| **c).map(exposed_faces).sum();
+    assert_eq!(surface, 10);
+    assert_eq!(
+      grid
+        .cell((0, 1, 1))
+        .travel((1, 0, 0))
+        .filter(|c| **c)
+        .count(),
+      2
+    );
+
+    grid.expand_to_include((-1, 0, 3), false);
+    assert_eq!(grid.size(), [4, 3, 4]);
+    assert!(grid[(2, 1, 1)]);
+    assert_eq!(grid.coords().count(), 48);
+
+    let mut sparse = [((0, 0, 0), 1), ((2, -1, 5), 2)]
+      .into_iter()
+      .collect::<SparseGrid3<u8>>();
+    assert_eq!(sparse.bounds(), Some(((0, -1, 0).into(), (2, 0, 5).into())));
+    assert_eq!(sparse[(1, 1, 1)], 0);
+    assert_eq!(sparse.cell((1, 0, 1)).count_neighbours(|&n| n > 0), 1);
+    assert_eq!(sparse.cell(Coord3::ORIGIN).neighbours().count(), 26);
+    assert_eq!(sparse.cell((2, 0, 5)).travel((1, 0, 0)).count(), 0);
+    sparse.remove((2, -1, 5));
+    assert_eq!(sparse.bounds(), Some((Coord3::ORIGIN, Coord3::ORIGIN)));
+  }
+}
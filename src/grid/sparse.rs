@@ -0,0 +1,196 @@
+use std::{
+  fmt::{Display, Formatter, Result as FmtResult},
+  ops::{Index, RangeInclusive},
+};
+
+use super::{
+  Coord, CoordN, GridCell, GridSource, SparseGridN, display, render, render_with_rulers,
+};
+
+pub type SparseCell<'a, T> = GridCell<'a, T, SparseGrid<T>>;
+
+/// An unbounded grid that only stores the cells that have been set, reading every other
+/// coord as a default value. Coords may be negative, and the grid's bounds grow and shrink
+/// to fit the cells that have been set. It's a 2D `SparseGridN` with the `GridCell` API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+  cells: SparseGridN<T, 2>,
+}
+
+impl<T> SparseGrid<T> {
+  pub fn new(default: T) -> Self {
+    Self {
+      cells: SparseGridN::new(default),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.cells.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.cells.is_empty()
+  }
+
+  /// Sets the value of a cell, returning its previous value if it had been set.
+  pub fn insert<C: Into<Coord>>(&mut self, coord: C, value: T) -> Option<T> {
+    self.cells.insert(coord.into(), value)
+  }
+
+  /// Unsets a cell, so it reads as the default value again.
+  pub fn remove<C: Into<Coord>>(&mut self, coord: C) -> Option<T> {
+    self.cells.remove(coord.into())
+  }
+
+  /// The value of a cell, if it has been set.
+  pub fn get<C: Into<Coord>>(&self, coord: C) -> Option<&T> {
+    self.cells.get(coord.into())
+  }
+
+  pub fn get_mut<C: Into<Coord>>(&mut self, coord: C) -> Option<&mut T> {
+    self.cells.get_mut(coord.into())
+  }
+
+  pub fn cell<C: Into<Coord>>(&self, coord: C) -> SparseCell<'_, T> {
+    GridCell::new(coord.into(), self)
+  }
+
+  /// The coords of every cell that has been set, in no particular order.
+  pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+    self.cells.coords().map(Coord::from)
+  }
+
+  /// Every cell that has been set, in no particular order.
+  pub fn cells(&self) -> impl Iterator<Item = SparseCell<'_, T>> {
+    self.coords().map(|coord| self.cell(coord))
+  }
+
+  /// The top-left and bottom-right corners of the smallest rectangle containing every cell
+  /// that has been set, or `None` if the grid is empty.
+  pub fn bounds(&self) -> Option<(Coord, Coord)> {
+    let (min, max) = self.cells.bounds()?;
+    Some((min.into(), max.into()))
+  }
+
+  /// Renders the grid's bounding box as text, one line per row, mapping each cell to a
+  /// character.
+  pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+    render(self, f)
+  }
+
+  /// Renders the grid like `render`, with row and column numbers.
+  pub fn render_with_rulers(&self, f: impl Fn(&T) -> char) -> String {
+    render_with_rulers(self, f)
+  }
+
+  /// The range of coords the bounding box covers along one axis, which is empty if the
+  /// grid is.
+  fn axis_range(&self, axis: usize) -> RangeInclusive<isize> {
+    let empty = (CoordN([0; 2]), CoordN([-1; 2]));
+    let (min, max) = self.cells.bounds().unwrap_or(empty);
+    min[axis]..=max[axis]
+  }
+}
+
+impl<T> GridSource<T> for SparseGrid<T> {
+  fn contains(&self, coord: Coord) -> bool {
+    self.row_range().contains(&coord.row) && self.col_range().contains(&coord.col)
+  }
+
+  fn value_at(&self, coord: Coord) -> &T {
+    &self.cells[coord]
+  }
+
+  /// Every coord is on the grid, so cells at the edge of the bounding box still see their
+  /// neighbours outside it (which read as the default value).
+  fn resolve(&self, coord: Coord) -> Option<Coord> {
+    Some(coord)
+  }
+
+  fn row_range(&self) -> RangeInclusive<isize> {
+    self.axis_range(0)
+  }
+
+  fn col_range(&self) -> RangeInclusive<isize> {
+    self.axis_range(1)
+  }
+}
+
+impl<T: Default> Default for SparseGrid<T> {
+  fn default() -> Self {
+    Self::new(T::default())
+  }
+}
+
+impl<T: Default, C: Into<Coord>> FromIterator<(C, T)> for SparseGrid<T> {
+  fn from_iter<I: IntoIterator<Item = (C, T)>>(iter: I) -> Self {
+    let mut grid = Self::default();
+    for (coord, value) in iter {
+      grid.insert(coord, value);
+    }
+    grid
+  }
+}
+
+impl<T, C: Into<Coord>> Index<C> for SparseGrid<T> {
+  type Output = T;
+
+  fn index(&self, coord: C) -> &Self::Output {
+    self.value_at(coord.into())
+  }
+}
+
+/// Displays the grid's bounding box, using each cell's `Display` impl (which is expected to
+/// produce exactly one character).
+impl<T: Display> Display for SparseGrid<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    display(self, f)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grid::Direction;
+
+  #[test]
+  fn test_sparse_grid() {
+    let mut grid = SparseGrid::new('.');
+    grid.insert((-1, -2), '#');
+    grid.insert((1, 0), '#');
+    assert_eq!(grid.bounds(), Some(((-1, -2).into(), (1, 0).into())));
+    assert_eq!(grid.to_string(), "#..\n...\n..#\n");
+
+    let cell = grid.cell((0, -1));
+    assert_eq!(cell.count_neighbours(|&c| c == '#'), 2);
+    assert_eq!(cell.travel(Direction::W).count(), 1);
+    assert_eq!(*cell, '.');
+
+    grid.remove((1, 0));
+    assert_eq!(grid.bounds(), Some(((-1, -2).into(), (-1, -2).into())));
+    grid.remove((-1, -2));
+    assert_eq!(grid.bounds(), None);
+    assert_eq!(grid.render(|&c| c), "");
+  }
+
+  #[test]
+  fn test_neighbours_outside_bounds() {
+    let mut grid = SparseGrid::new(false);
+    grid.insert((0, 0), true);
+    let cell = grid.cell((0, 0));
+    assert_eq!(cell.neighbours().count(), 8);
+    assert!(cell.neighbours().all(|neighbour| !*neighbour));
+    assert_eq!(cell.neighbour(Direction::N).unwrap().coord, (-1, 0).into());
+    assert_eq!(cell.travel(Direction::E).count(), 0);
+
+    // The empty cells next to a live one are the frontier that might come alive next
+    grid.insert((0, 1), true);
+    let frontier = grid
+      .cells()
+      .flat_map(|cell| cell.neighbours())
+      .filter(|neighbour| !**neighbour)
+      .map(|neighbour| neighbour.coord)
+      .collect::<std::collections::HashSet<_>>();
+    assert_eq!(frontier.len(), 10);
+  }
+}
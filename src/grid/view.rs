@@ -0,0 +1,183 @@
+use std::ops::{Index, RangeInclusive};
+
+use super::{Coord, Grid, GridCell, GridSource, render};
+
+pub type ViewCell<'a, 'g, T> = GridCell<'a, T, GridView<'g, T>>;
+
+/// A rectangular region of a `Grid`, borrowed rather than copied. Cells in a view keep
+/// the coords they have in the full grid, but only see neighbours inside the view.
+pub struct GridView<'g, T> {
+  source: &'g Grid<T>,
+  min_row: isize,
+  max_row: isize,
+  min_col: isize,
+  max_col: isize,
+}
+
+impl<T> Copy for GridView<'_, T> {}
+impl<T> Clone for GridView<'_, T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<'g, T> GridView<'g, T> {
+  pub(super) fn new(source: &'g Grid<T>, min: Coord, max: Coord) -> Self {
+    assert!(source.contains(min) && source.contains(max));
+    assert!(min.row <= max.row && min.col <= max.col);
+    Self {
+      source,
+      min_row: min.row,
+      max_row: max.row,
+      min_col: min.col,
+      max_col: max.col,
+    }
+  }
+
+  pub fn cell<C: Into<Coord>>(&self, coord: C) -> ViewCell<'_, 'g, T> {
+    GridCell::new(coord.into(), self)
+  }
+
+  pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = ViewCell<'_, 'g, T>>> {
+    (self.min_row..=self.max_row)
+      .map(move |row| (self.min_col..=self.max_col).map(move |col| self.cell(Coord { row, col })))
+  }
+
+  pub fn coords(&self) -> impl Iterator<Item = Coord> + 'static {
+    let rows = self.min_row..=self.max_row;
+    let cols = self.min_col..=self.max_col;
+    rows.flat_map(move |row| cols.clone().map(move |col| Coord { row, col }))
+  }
+
+  pub fn cells(&self) -> impl Iterator<Item = ViewCell<'_, 'g, T>> {
+    self.coords().map(|coord| self.cell(coord))
+  }
+
+  /// The top-left and bottom-right corners of the view, in the full grid's coords.
+  pub fn bounds(&self) -> (Coord, Coord) {
+    (
+      Coord::from((self.min_row, self.min_col)),
+      Coord::from((self.max_row, self.max_col)),
+    )
+  }
+
+  pub fn height(&self) -> usize {
+    (self.max_row - self.min_row + 1) as usize
+  }
+
+  pub fn width(&self) -> usize {
+    (self.max_col - self.min_col + 1) as usize
+  }
+
+  /// Copies the view out into a grid of its own, with its top-left corner at (0, 0).
+  pub fn to_grid(&self) -> Grid<T>
+  where
+    T: Clone,
+  {
+    Grid::new(
+      self
+        .rows()
+        .map(|row| row.map(|cell| cell.value().clone()).collect())
+        .collect(),
+    )
+  }
+
+  pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+    render(self, f)
+  }
+}
+
+impl<T> GridSource<T> for GridView<'_, T> {
+  fn contains(&self, coord: Coord) -> bool {
+    self.row_range().contains(&coord.row) && self.col_range().contains(&coord.col)
+  }
+
+  fn value_at(&self, coord: Coord) -> &T {
+    assert!(self.contains(coord));
+    &self.source[coord]
+  }
+
+  fn row_range(&self) -> RangeInclusive<isize> {
+    self.min_row..=self.max_row
+  }
+
+  fn col_range(&self) -> RangeInclusive<isize> {
+    self.min_col..=self.max_col
+  }
+}
+
+impl<T, C: Into<Coord>> Index<C> for GridView<'_, T> {
+  type Output = T;
+
+  fn index(&self, coord: C) -> &Self::Output {
+    self.value_at(coord.into())
+  }
+}
+
+impl<T> Grid<T> {
+  /// Borrows the region from `min` to `max` (inclusive) as a view.
+  pub fn view(&self, min: impl Into<Coord>, max: impl Into<Coord>) -> GridView<'_, T> {
+    GridView::new(self, min.into(), max.into())
+  }
+
+  /// Every `height` by `width` region of the grid, in row-major order of their top-left
+  /// corners.
+  pub fn windows(&self, height: usize, width: usize) -> impl Iterator<Item = GridView<'_, T>> {
+    assert!(height > 0 && width > 0);
+    let size = Coord::from((height as isize - 1, width as isize - 1));
+    let rows = self.min_row..=self.max_row - size.row;
+    let cols = self.min_col..=self.max_col - size.col;
+    rows
+      .flat_map(move |row| cols.clone().map(move |col| Coord { row, col }))
+      .map(move |corner| self.view(corner, corner + size))
+  }
+
+  /// Each cell along with the square of cells up to `radius` steps away from it (so a
+  /// radius of 1 gives a 3x3 kernel), clipped to the edges of the grid.
+  pub fn kernels(&self, radius: usize) -> impl Iterator<Item = (GridCell<'_, T>, GridView<'_, T>)> {
+    let radius = Coord::from((radius as isize, radius as isize));
+    let (min, max) = self.bounds();
+    self.cells().map(move |cell| {
+      let from = cell.coord - radius;
+      let to = cell.coord + radius;
+      let from = Coord::from((from.row.max(min.row), from.col.max(min.col)));
+      let to = Coord::from((to.row.min(max.row), to.col.min(max.col)));
+      (cell, self.view(from, to))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grid::Direction;
+
+  #[test]
+  fn test_views() {
+    let grid = Grid::parse("abcd\nefgh\nijkl", |c| c);
+    let view = grid.view((1, 1), (2, 2));
+    assert_eq!(view.render(|&c| c), "fg\njk\n");
+    assert_eq!(view[(2, 1)], 'j');
+    assert_eq!(view.cell((1, 1)).neighbours().count(), 3);
+    assert!(view.cell((1, 1)).neighbour(Direction::N).is_none());
+    assert_eq!(view.to_grid(), Grid::parse("fg\njk", |c| c));
+
+    let windows = grid
+      .windows(2, 3)
+      .map(|w| w.render(|&c| c))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      windows,
+      ["abc\nefg\n", "bcd\nfgh\n", "efg\nijk\n", "fgh\njkl\n"]
+    );
+    assert_eq!(grid.windows(4, 1).count(), 0);
+
+    let kernels = grid
+      .kernels(1)
+      .map(|(cell, kernel)| (*cell, kernel.height() * kernel.width()))
+      .collect::<Vec<_>>();
+    assert_eq!(kernels[0], ('a', 4));
+    assert_eq!(kernels[5], ('f', 9));
+    assert_eq!(kernels[11], ('l', 4));
+  }
+}
@@ -0,0 +1,129 @@
+use std::ops::{Index, RangeInclusive};
+
+use super::{Coord, Grid, GridCell, GridSource};
+
+pub type WrappingCell<'a, 'g, T> = GridCell<'a, T, Wrapping<'g, T>>;
+
+/// A `Grid` whose edges wrap around, so stepping off one side comes back in on the opposite
+/// side. Every cell has a full set of neighbours, and distances are measured the short way
+/// round.
+pub struct Wrapping<'g, T> {
+  source: &'g Grid<T>,
+}
+
+impl<T> Copy for Wrapping<'_, T> {}
+impl<T> Clone for Wrapping<'_, T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<'g, T> Wrapping<'g, T> {
+  /// The cell at a coord, which may be outside the grid's bounds.
+  pub fn cell<C: Into<Coord>>(&self, coord: C) -> WrappingCell<'_, 'g, T> {
+    GridCell::new(self.wrap(coord.into()), self)
+  }
+
+  pub fn cells(&self) -> impl Iterator<Item = WrappingCell<'_, 'g, T>> {
+    self.source.coords().map(|coord| self.cell(coord))
+  }
+
+  /// Maps any coord to the coord within the grid's bounds that it lands on.
+  pub fn wrap(&self, coord: Coord) -> Coord {
+    let (min, _) = self.source.bounds();
+    let height = self.source.height() as isize;
+    let width = self.source.width() as isize;
+    Coord {
+      row: min.row + (coord.row - min.row).rem_euclid(height),
+      col: min.col + (coord.col - min.col).rem_euclid(width),
+    }
+  }
+
+  /// The shortest row and column distances between two coords, going either way round.
+  fn axis_distances(&self, a: Coord, b: Coord) -> (usize, usize) {
+    let (a, b) = (self.wrap(a), self.wrap(b));
+    let rows = a.row.abs_diff(b.row);
+    let cols = a.col.abs_diff(b.col);
+    (
+      rows.min(self.source.height() - rows),
+      cols.min(self.source.width() - cols),
+    )
+  }
+
+  /// The number of orthogonal steps needed to get from one coord to the other, allowing
+  /// steps across the edges.
+  pub fn manhattan_distance(&self, a: impl Into<Coord>, b: impl Into<Coord>) -> usize {
+    let (rows, cols) = self.axis_distances(a.into(), b.into());
+    rows + cols
+  }
+
+  /// The number of king's moves needed to get from one coord to the other, allowing moves
+  /// across the edges.
+  pub fn chebyshev_distance(&self, a: impl Into<Coord>, b: impl Into<Coord>) -> usize {
+    let (rows, cols) = self.axis_distances(a.into(), b.into());
+    rows.max(cols)
+  }
+}
+
+impl<T> GridSource<T> for Wrapping<'_, T> {
+  fn contains(&self, coord: Coord) -> bool {
+    self.source.contains(coord)
+  }
+
+  fn value_at(&self, coord: Coord) -> &T {
+    &self.source[self.wrap(coord)]
+  }
+
+  fn resolve(&self, coord: Coord) -> Option<Coord> {
+    Some(self.wrap(coord))
+  }
+
+  fn row_range(&self) -> RangeInclusive<isize> {
+    self.source.row_range()
+  }
+
+  fn col_range(&self) -> RangeInclusive<isize> {
+    self.source.col_range()
+  }
+}
+
+impl<T, C: Into<Coord>> Index<C> for Wrapping<'_, T> {
+  type Output = T;
+
+  fn index(&self, coord: C) -> &Self::Output {
+    self.value_at(coord.into())
+  }
+}
+
+impl<T> Grid<T> {
+  /// Borrows the grid as a torus, with its edges wrapping around to meet each other.
+  pub fn wrapping(&self) -> Wrapping<'_, T> {
+    Wrapping { source: self }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grid::Direction;
+
+  #[test]
+  fn test_wrapping() {
+    let grid = Grid::parse("abc\ndef\nghi\njkl", |c| c);
+    let torus = grid.wrapping();
+    assert_eq!(torus[(-1, 3)], 'j');
+    assert_eq!(torus[(9, -4)], 'f');
+
+    let corner = torus.cell((0, 0));
+    assert_eq!(corner.neighbours().count(), 8);
+    assert_eq!(*corner.neighbour(Direction::NW).unwrap(), 'l');
+    assert_eq!(
+      corner.travel(Direction::W).map(|c| *c).collect::<String>(),
+      "cb"
+    );
+    assert_eq!(corner.travel(Direction::SE).count(), 11);
+
+    assert_eq!(torus.manhattan_distance((0, 0), (3, 2)), 2);
+    assert_eq!(torus.chebyshev_distance((0, 0), (2, 1)), 2);
+  }
+}
@@ -0,0 +1,222 @@
+use std::{
+  fs::{self, OpenOptions},
+  io::Write,
+  path::PathBuf,
+  process::Command,
+  time::Duration,
+};
+
+const DEFAULT_PATH: &str = ".aoc-history.tsv";
+
+/// The timings from running one part of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+  pub year: u16,
+  pub day: u8,
+  pub part: u8,
+  pub profile: Profile,
+  pub revision: String,
+  pub parse: Duration,
+  pub solve: Duration,
+}
+
+impl Run {
+  pub fn total(&self) -> Duration {
+    self.parse + self.solve
+  }
+
+  fn parse_line(line: &str) -> Option<Run> {
+    let mut fields = line.split('\t');
+    let mut next = || fields.next();
+    Some(Run {
+      year: next()?.parse().ok()?,
+      day: next()?.parse().ok()?,
+      part: next()?.parse().ok()?,
+      profile: Profile::from_name(next()?)?,
+      revision: next()?.to_string(),
+      parse: Duration::from_nanos(next()?.parse().ok()?),
+      solve: Duration::from_nanos(next()?.parse().ok()?),
+    })
+  }
+
+  fn to_line(&self) -> String {
+    format!(
+      "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+      self.year,
+      self.day,
+      self.part,
+      self.profile.name(),
+      self.revision,
+      self.parse.as_nanos(),
+      self.solve.as_nanos()
+    )
+  }
+}
+
+/// Which build profile a run was made with. Debug builds are far slower, so runs are only
+/// compared against others from the same profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+  Debug,
+  Release,
+}
+
+impl Profile {
+  /// The profile this binary was built with.
+  pub fn current() -> Self {
+    if cfg!(debug_assertions) {
+      Profile::Debug
+    } else {
+      Profile::Release
+    }
+  }
+
+  fn name(&self) -> &'static str {
+    match self {
+      Profile::Debug => "debug",
+      Profile::Release => "release",
+    }
+  }
+
+  fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "debug" => Some(Profile::Debug),
+      "release" => Some(Profile::Release),
+      _ => None,
+    }
+  }
+}
+
+/// Timings of previous runs, persisted as a tab-separated file in the working directory
+/// (or wherever `AOC_HISTORY_FILE` points).
+pub struct History {
+  path: PathBuf,
+  revision: String,
+  runs: Vec<Run>,
+}
+
+impl History {
+  pub fn load() -> Self {
+    let path = std::env::var_os("AOC_HISTORY_FILE")
+      .map(PathBuf::from)
+      .unwrap_or_else(|| PathBuf::from(DEFAULT_PATH));
+    let runs = fs::read_to_string(&path)
+      .unwrap_or_default()
+      .lines()
+      .filter_map(Run::parse_line)
+      .collect();
+    Self {
+      path,
+      revision: git_revision(),
+      runs,
+    }
+  }
+
+  /// The git revision new runs are being made at.
+  pub fn revision(&self) -> &str {
+    &self.revision
+  }
+
+  /// The most recent earlier run of the same part, built with the same profile.
+  pub fn previous(&self, run: &Run) -> Option<&Run> {
+    self.runs_like(run).last()
+  }
+
+  /// The fastest earlier run of the same part, built with the same profile.
+  pub fn best(&self, run: &Run) -> Option<&Run> {
+    self.runs_like(run).min_by_key(|run| run.total())
+  }
+
+  /// Appends the run to the history file, warning rather than failing if it can't be
+  /// written.
+  pub fn record(&mut self, run: Run) {
+    let written = OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&self.path)
+      .and_then(|mut file| writeln!(file, "{}", run.to_line()));
+    if let Err(err) = written {
+      eprintln!("Failed to record timings in {}: {err}", self.path.display());
+    }
+    self.runs.push(run);
+  }
+
+  fn runs_like(&self, other: &Run) -> impl Iterator<Item = &Run> {
+    self.runs.iter().filter(move |run| {
+      (run.year, run.day, run.part, run.profile)
+        == (other.year, other.day, other.part, other.profile)
+    })
+  }
+}
+
+/// Returns the short hash of the current git revision, marked with `+` if the working tree
+/// has uncommitted changes, or `-` outside of a git checkout.
+fn git_revision() -> String {
+  let git = |args: &[&str]| {
+    Command::new("git")
+      .args(args)
+      .output()
+      .ok()
+      .filter(|output| output.status.success())
+      .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+  };
+
+  match git(&["rev-parse", "--short", "HEAD"]) {
+    Some(hash) if git(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty()) => {
+      format!("{hash}+")
+    }
+    Some(hash) => hash,
+    None => "-".to_string(),
+  }
+}
+
+/// The relative change from `before` to `after`, as a percentage.
+pub fn percent_change(before: Duration, after: Duration) -> f64 {
+  (after.as_secs_f64() - before.as_secs_f64()) / before.as_secs_f64() * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_line_round_trip() {
+    let run = Run {
+      year: 2025,
+      day: 4,
+      part: 2,
+      profile: Profile::Release,
+      revision: "abc1234+".to_string(),
+      parse: Duration::from_micros(150),
+      solve: Duration::from_millis(12),
+    };
+    assert_eq!(Run::parse_line(&run.to_line()), Some(run));
+  }
+
+  #[test]
+  fn test_compares_same_profile() {
+    let run = |profile, millis| Run {
+      year: 2025,
+      day: 4,
+      part: 1,
+      profile,
+      revision: "abc1234".to_string(),
+      parse: Duration::ZERO,
+      solve: Duration::from_millis(millis),
+    };
+    let history = History {
+      path: PathBuf::new(),
+      revision: "abc1234".to_string(),
+      runs: vec![
+        run(Profile::Release, 5),
+        run(Profile::Debug, 90),
+        run(Profile::Release, 7),
+      ],
+    };
+    let current = run(Profile::Release, 6);
+    assert_eq!(history.previous(&current).unwrap().solve.as_millis(), 7);
+    assert_eq!(history.best(&current).unwrap().solve.as_millis(), 5);
+    let current = run(Profile::Debug, 80);
+    assert_eq!(history.best(&current).unwrap().solve.as_millis(), 90);
+  }
+}
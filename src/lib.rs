@@ -1,5 +1,7 @@
 mod exts;
+mod history;
 
+pub mod answer;
 pub mod grid;
 pub mod prelude;
 pub mod runner;
@@ -1,5 +1,4 @@
-use core::panic;
-
+pub use crate::answer::Answer;
 pub use crate::exts::*;
 pub use crate::solution;
 
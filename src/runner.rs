@@ -1,10 +1,20 @@
 use std::{
+  backtrace::{Backtrace, BacktraceStatus},
+  cell::Cell,
   fmt::{Display, Formatter, Result as FmtResult},
-  time::Instant,
+  fs,
+  panic::{self, AssertUnwindSafe},
+  path::{Path, PathBuf},
+  time::{Duration, Instant},
 };
 
 use clap::Parser;
 
+use crate::{
+  answer::Answer,
+  history::{self, History, Profile, Run},
+};
+
 #[macro_export]
 macro_rules! solution {
     ($($config:tt)*) => {
@@ -14,7 +24,7 @@ macro_rules! solution {
     };
 }
 
-pub struct Solution<I: Clone, S1: ToString, S2: ToString> {
+pub struct Solution<I: Clone, S1: Into<Answer>, S2: Into<Answer>> {
   pub day: u8,
   pub year: u16,
   pub parse: fn(&str) -> I,
@@ -22,45 +32,280 @@ pub struct Solution<I: Clone, S1: ToString, S2: ToString> {
   pub part_2: fn(I) -> S2,
 }
 
-impl<I: Clone, S1: ToString, S2: ToString> Solution<I, S1, S2> {
+impl<I: Clone, S1: Into<Answer>, S2: Into<Answer>> Solution<I, S1, S2> {
   pub fn run(&self) {
     let options = CLIOptions::parse();
+    install_panic_hook();
+    if let Some(dir) = &options.batch {
+      self.run_batch(dir);
+      return;
+    }
+
     let client = libaoc::Client::new().expect("Failed to create AoC client");
     let puzzle_id = (self.year, self.day);
     let input = client
       .get_input(&puzzle_id)
       .expect("Failed to fetch puzzle input");
-    let (parsed, parse_time) = time(|| (self.parse)(&input));
-
-    if options.part.includes_part_1() {
-      let (result, solve_time) = time(|| (self.part_1)(parsed.clone()));
-      let result = result.to_string();
+    let (parsed, parse_time) = time(|| catch_panics(|| (self.parse)(&input)));
+    let parsed = match parsed {
+      Ok(parsed) => parsed,
+      Err(failure) => {
+        println!("Parse: {failure}");
+        std::process::exit(1);
+      }
+    };
+    let mut history = (!options.no_history).then(History::load);
+    let mut crashed = false;
 
-      println!("Part 1: {result} (parse {parse_time:?}, solve {solve_time:?})");
+    for part in [1, 2]
+      .into_iter()
+      .filter(|&part| options.part.includes(part))
+    {
+      let result = match self.solve_part(part, &parsed) {
+        (Ok(result), solve_time) => {
+          println!("Part {part}: {result} (parse {parse_time:?}, solve {solve_time:?})");
+          if let Some(history) = &mut history {
+            self.record_timings(history, &options, part, parse_time, solve_time);
+          }
+          result
+        }
+        (Err(failure), _) => {
+          println!("Part {part}: {failure}");
+          crashed |= !matches!(failure, Failure::NotImplemented);
+          continue;
+        }
+      };
 
       if options.submit {
+        let text = result
+          .text()
+          .unwrap_or_else(|| panic!("Unable to read the letters in the answer for Part {part}"));
         client
-          .submit(&puzzle_id, Some(1), result)
-          .expect("Failed to submit answer for Part 1");
+          .submit(&puzzle_id, Some(part), text)
+          .unwrap_or_else(|err| panic!("Failed to submit answer for Part {part}: {err}"));
       }
     }
 
-    if options.part.includes_part_2() {
-      let (result, solve_time) = time(|| (self.part_2)(parsed.clone()));
-      let result = result.to_string();
+    if crashed {
+      std::process::exit(1);
+    }
+  }
+
+  /// Reports how a run's timings compare to the previous and best runs of the same part,
+  /// then adds it to the history.
+  fn record_timings(
+    &self,
+    history: &mut History,
+    options: &CLIOptions,
+    part: u8,
+    parse: Duration,
+    solve: Duration,
+  ) {
+    let run = Run {
+      year: self.year,
+      day: self.day,
+      part,
+      profile: Profile::current(),
+      revision: history.revision().to_string(),
+      parse,
+      solve,
+    };
+    let previous = history.previous(&run);
+    let best = history.best(&run);
 
-      println!("Part 2: {result} (parse {parse_time:?}, solve {solve_time:?})");
+    if let (Some(previous), Some(best)) = (previous, best) {
+      let vs_previous = history::percent_change(previous.total(), run.total());
+      let vs_best = history::percent_change(best.total(), run.total());
+      println!(
+        "  {vs_previous:+.1}% vs previous ({:?} at {}), {vs_best:+.1}% vs best ({:?} at {})",
+        previous.total(),
+        previous.revision,
+        best.total(),
+        best.revision,
+      );
 
-      if options.submit {
-        client
-          .submit(&puzzle_id, Some(2), result)
-          .expect("Failed to submit answer for Part 2");
+      if vs_previous > options.regression_threshold {
+        println!(
+          "  Warning: Part {} regressed by {vs_previous:.1}% since the previous run",
+          run.part
+        );
+      }
+    }
+
+    history.record(run);
+  }
+
+  /// Runs both parts against every `<name>.txt` input in the given directory, checking
+  /// the results against `<name>.answers` where one exists. Exits with a failure status
+  /// if any answer doesn't match.
+  fn run_batch(&self, dir: &Path) {
+    let mut inputs = fs::read_dir(dir)
+      .expect("Failed to read batch directory")
+      .map(|entry| entry.expect("Failed to read batch directory entry").path())
+      .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+      .collect::<Vec<_>>();
+    inputs.sort();
+
+    let mut mismatches = 0;
+    for path in &inputs {
+      let name = path.file_stem().unwrap_or_default().to_string_lossy();
+      let input = fs::read_to_string(path).expect("Failed to read batch input");
+      let expected = read_expected_answers(&path.with_extension("answers"));
+      let (parsed, parse_time) = time(|| catch_panics(|| (self.parse)(&input)));
+      let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(failure) => {
+          println!("{name} (parse: {failure})");
+          mismatches += expected.iter().flatten().count();
+          continue;
+        }
+      };
+
+      println!("{name} (parse {parse_time:?})");
+      for (part, expected) in [1, 2].into_iter().zip(expected) {
+        let (result, solve_time) = match self.solve_part(part, &parsed) {
+          (Ok(result), solve_time) => (result, solve_time),
+          (Err(failure), _) => {
+            println!("  Part {part}: {failure}");
+            mismatches += expected.is_some() as usize;
+            continue;
+          }
+        };
+
+        let (status, matched) = check_answer(&result, expected.as_deref());
+        mismatches += !matched as usize;
+
+        println!("  Part {part}: {result} (solve {solve_time:?}){status}");
       }
     }
+
+    println!("{} inputs, {mismatches} mismatched answers", inputs.len());
+    if mismatches > 0 {
+      std::process::exit(1);
+    }
+  }
+
+  /// Solves one part, catching any panic so the remaining parts can still run.
+  fn solve_part(&self, part: u8, parsed: &I) -> (Result<Answer, Failure>, Duration) {
+    time(|| {
+      catch_panics(|| match part {
+        1 => (self.part_1)(parsed.clone()).into(),
+        2 => (self.part_2)(parsed.clone()).into(),
+        _ => panic!("Invalid part: {part}"),
+      })
+    })
+  }
+}
+
+/// Reads the expected answers for a batch input: the first line holds the answer for
+/// Part 1 and the second for Part 2. Missing or blank lines aren't checked.
+fn read_expected_answers(path: &Path) -> [Option<String>; 2] {
+  let contents = fs::read_to_string(path).unwrap_or_default();
+  let mut lines = contents.lines().map(|line| {
+    Some(line.trim())
+      .filter(|line| !line.is_empty())
+      .map(String::from)
+  });
+  [lines.next().flatten(), lines.next().flatten()]
+}
+
+/// The note shown after an answer in batch mode, and whether the answer matched the expected
+/// one (which it always does when there's nothing to check it against).
+fn check_answer(answer: &Answer, expected: Option<&str>) -> (String, bool) {
+  match (answer.text(), expected) {
+    (_, None) => (String::new(), true),
+    (Some(actual), Some(expected)) if actual == expected => (" ✓".to_string(), true),
+    (_, Some(expected)) => (format!(" ✗ expected {expected}"), false),
   }
 }
 
-fn time<S>(f: impl FnOnce() -> S) -> (S, std::time::Duration) {
+/// The panic messages of the placeholder parts in the prelude, so the runner can tell a part
+/// that hasn't been written yet apart from one that crashed.
+const NOT_IMPLEMENTED: [&str; 2] = ["Part 1 not implemented", "Part 2 not implemented"];
+
+/// Why a phase of a solution failed to produce a result.
+pub enum Failure {
+  NotImplemented,
+  Panic {
+    message: String,
+    location: Option<String>,
+    backtrace: Option<Backtrace>,
+  },
+}
+
+impl Display for Failure {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let (message, location, backtrace) = match self {
+      Failure::NotImplemented => return write!(f, "not implemented"),
+      Failure::Panic {
+        message,
+        location,
+        backtrace,
+      } => (message, location, backtrace),
+    };
+    match location {
+      Some(location) => write!(f, "panicked at {location}: {message}")?,
+      None => write!(f, "panicked: {message}")?,
+    }
+    match backtrace {
+      Some(backtrace) if backtrace.status() == BacktraceStatus::Captured => {
+        write!(f, "\n{backtrace}")
+      }
+      _ => Ok(()),
+    }
+  }
+}
+
+thread_local! {
+  /// Whether `catch_panics` is running on this thread, so the panic hook knows to leave
+  /// reporting the panic to it.
+  static CATCHING_PANICS: Cell<bool> = const { Cell::new(false) };
+
+  /// Where the last panic caught on this thread happened, and its backtrace (when enabled by
+  /// `RUST_BACKTRACE`).
+  static CAUGHT_PANIC: Cell<Option<(Option<String>, Backtrace)>> = const { Cell::new(None) };
+}
+
+/// Installs a panic hook that records where panics inside `catch_panics` happen instead of
+/// printing them, since the failure gets reported alongside the other results. Any other
+/// panic, including those on threads spawned by a solution, is passed on to the default hook.
+fn install_panic_hook() {
+  let default_hook = panic::take_hook();
+  panic::set_hook(Box::new(move |info| {
+    if !CATCHING_PANICS.get() {
+      return default_hook(info);
+    }
+    let location = info.location().map(|l| l.to_string());
+    CAUGHT_PANIC.set(Some((location, Backtrace::capture())));
+  }));
+}
+
+/// Runs `f`, turning any panic into a `Failure` instead of letting it unwind further.
+fn catch_panics<S>(f: impl FnOnce() -> S) -> Result<S, Failure> {
+  let catching = CATCHING_PANICS.replace(true);
+  let result = panic::catch_unwind(AssertUnwindSafe(f));
+  CATCHING_PANICS.set(catching);
+
+  result.map_err(|payload| {
+    let message = payload
+      .downcast_ref::<&str>()
+      .map(|s| s.to_string())
+      .or_else(|| payload.downcast_ref::<String>().cloned())
+      .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    if NOT_IMPLEMENTED.contains(&message.as_str()) {
+      return Failure::NotImplemented;
+    }
+
+    let (location, backtrace) = CAUGHT_PANIC.take().unzip();
+    Failure::Panic {
+      message,
+      location: location.flatten(),
+      backtrace,
+    }
+  })
+}
+
+fn time<S>(f: impl FnOnce() -> S) -> (S, Duration) {
   let start = Instant::now();
   let result = f();
   let end = Instant::now();
@@ -76,6 +321,21 @@ pub struct CLIOptions {
   /// Whether the answer should be submitted automatically
   #[arg(short, long, default_value_t = false)]
   pub submit: bool,
+
+  /// Run both parts against each `<name>.txt` input in a directory, checking answers
+  /// against `<name>.answers` files, instead of fetching the puzzle input
+  #[arg(short, long, conflicts_with = "submit")]
+  pub batch: Option<PathBuf>,
+
+  /// How much slower (in percent) a part can run than it did previously before a
+  /// regression warning is shown
+  #[arg(long, default_value_t = 10.0)]
+  pub regression_threshold: f64,
+
+  /// Don't record timings or compare them against previous runs, for when other work is
+  /// running at the same time and the timings can't be trusted
+  #[arg(long, default_value_t = false)]
+  pub no_history: bool,
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, clap::ValueEnum)]
@@ -99,6 +359,14 @@ impl Display for PartSelection {
 }
 
 impl PartSelection {
+  pub fn includes(&self, part: u8) -> bool {
+    match part {
+      1 => self.includes_part_1(),
+      2 => self.includes_part_2(),
+      _ => false,
+    }
+  }
+
   pub fn includes_part_1(&self) -> bool {
     matches!(self, PartSelection::One | PartSelection::Both)
   }
@@ -107,3 +375,86 @@ impl PartSelection {
     matches!(self, PartSelection::Two | PartSelection::Both)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::prelude;
+
+  #[test]
+  fn test_read_expected_answers() {
+    let dir = std::env::temp_dir().join(format!("aoc-answers-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let answers = |contents: &str| {
+      let path = dir.join("input.answers");
+      fs::write(&path, contents).unwrap();
+      read_expected_answers(&path)
+    };
+    let some = |answer: &str| Some(answer.to_string());
+    assert_eq!(answers("123\nABC\n"), [some("123"), some("ABC")]);
+    assert_eq!(answers("  42  "), [some("42"), None]);
+    assert_eq!(answers("\nABC\n"), [None, some("ABC")]);
+    assert_eq!(answers(""), [None, None]);
+    assert_eq!(
+      read_expected_answers(&dir.join("missing.answers")),
+      [None, None]
+    );
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_check_answer() {
+    let answer = Answer::from(42);
+    assert_eq!(check_answer(&answer, None), (String::new(), true));
+    assert_eq!(check_answer(&answer, Some("42")), (" ✓".to_string(), true));
+    assert_eq!(
+      check_answer(&answer, Some("41")),
+      (" ✗ expected 41".to_string(), false)
+    );
+    // Letters that can't be read never match
+    let unreadable = Answer::Letters(vec![vec![true]]);
+    assert!(!check_answer(&unreadable, Some("A")).1);
+  }
+
+  #[test]
+  fn test_placeholder_parts_are_not_implemented() {
+    let part_1 = catch_panics(|| prelude::part_1(String::new()));
+    assert!(matches!(part_1, Err(Failure::NotImplemented)));
+    let part_2 = catch_panics(|| prelude::part_2(String::new()));
+    assert!(matches!(part_2, Err(Failure::NotImplemented)));
+    assert!(catch_panics(|| 1).is_ok());
+  }
+
+  #[test]
+  fn test_panic_messages() {
+    let message = |failure: Result<(), Failure>| match failure {
+      Err(Failure::Panic { message, .. }) => message,
+      _ => panic!("Expected a panic"),
+    };
+    static HOOK: std::sync::Once = std::sync::Once::new();
+    HOOK.call_once(install_panic_hook);
+    let Err(Failure::Panic { location, .. }) = catch_panics(|| panic!("here")) else {
+      panic!("Expected a panic");
+    };
+    assert!(location.is_some_and(|location| location.starts_with("src/runner.rs:")));
+
+    assert_eq!(message(catch_panics(|| panic!("boom"))), "boom");
+    let x = 42;
+    assert_eq!(message(catch_panics(|| panic!("{}", x))), "42");
+    assert_eq!(
+      message(catch_panics(|| panic!("Part 3 not implemented"))),
+      "Part 3 not implemented"
+    );
+  }
+
+  #[test]
+  fn test_catching_is_restored() {
+    assert!(!CATCHING_PANICS.get());
+    let inner = catch_panics(|| {
+      assert!(CATCHING_PANICS.get());
+      catch_panics(|| panic!("inner")).is_err() && CATCHING_PANICS.get()
+    });
+    assert!(matches!(inner, Ok(true)));
+    assert!(!CATCHING_PANICS.get());
+  }
+}